@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::solution::{Answer, Part};
+
+/// Known-good answers, keyed by day, part and the hash of the input they were computed for.
+///
+/// The registry is stored as text, one answer per line: `<day> <part> <input hash> <answer>`.
+/// Empty lines and lines starting with `#` are ignored.
+#[derive(Debug, Default, PartialEq)]
+pub struct AnswerRegistry {
+    answers: BTreeMap<(u8, Part, u64), String>
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    /// `actual` is `None` if the solver didn't find an answer.
+    Fail { expected: String, actual: Option<String> },
+    Missing
+}
+
+/// Error for a malformed registry line, `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryError {
+    pub line: usize,
+    pub reason: String
+}
+
+impl Display for RegistryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid answer at line {}: {}", self.line, self.reason)
+    }
+}
+
+impl Error for RegistryError {
+}
+
+impl AnswerRegistry {
+    pub fn parse(s: &str) -> Result<Self, RegistryError> {
+        let mut answers = BTreeMap::<(u8, Part, u64), String>::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |reason: &str| RegistryError { line: index + 1, reason: String::from(reason) };
+            let mut fields = line.splitn(4, ' ');
+            let day = fields
+                .next()
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| error("Day must be a number!"))?;
+            let part = fields
+                .next()
+                .and_then(|part| part.parse::<u8>().ok())
+                .and_then(Part::from_number)
+                .ok_or_else(|| error("Part must be 1 or 2!"))?;
+            let input_hash = fields
+                .next()
+                .and_then(|input_hash| u64::from_str_radix(input_hash, 16).ok())
+                .ok_or_else(|| error("Input hash must be a hexadecimal number!"))?;
+            let answer = fields
+                .next()
+                .map(str::trim)
+                .filter(|answer| !answer.is_empty())
+                .ok_or_else(|| error("Answer is missing!"))?;
+
+            answers.insert((day, part, input_hash), String::from(answer));
+        }
+
+        Ok(Self { answers })
+    }
+
+    pub fn get(&self, day: u8, part: Part, input_hash: u64) -> Option<&str> {
+        self.answers
+            .get(&(day, part, input_hash))
+            .map(String::as_str)
+    }
+
+    /// Stores `answer`, returns `false` if an answer was already registered for the same key.
+    pub fn insert(&mut self, day: u8, part: Part, input_hash: u64, answer: &str) -> bool {
+        let key = (day, part, input_hash);
+        if self.answers.contains_key(&key) {
+            return false;
+        }
+
+        self.answers.insert(key, String::from(answer));
+        true
+    }
+
+    pub fn verify(&self, day: u8, input_hash: u64, answer: &Answer) -> Verdict {
+        match self.get(day, answer.part, input_hash) {
+            None => Verdict::Missing,
+            Some(expected) if answer.value.as_deref() == Some(expected) => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: String::from(expected),
+                actual: answer.value.clone()
+            }
+        }
+    }
+}
+
+impl Display for AnswerRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# day part input-hash answer")?;
+
+        for ((day, part, input_hash), answer) in &self.answers {
+            writeln!(f, "{day} {} {} {answer}", part.number(), format_hash(*input_hash))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 64 bit FNV-1a hash of `input`, stable across platforms and compiler versions.
+pub fn input_hash(input: &str) -> u64 {
+    input
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+pub fn format_hash(input_hash: u64) -> String {
+    format!("{input_hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::*;
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), 0xcbf29ce484222325);
+        assert_eq!(input_hash("a"), 0xaf63dc4c8601ec8c);
+        assert_ne!(input_hash("Time: 7\n"), input_hash("Time: 7"));
+        assert_eq!(format_hash(0xaf), "00000000000000af");
+    }
+
+    #[test]
+    fn test_parse() {
+        let registry = AnswerRegistry::parse("# day part input-hash answer
+
+6 1 00000000000000af 288
+6 2 00000000000000af 71503
+7 1 af63dc4c8601ec8c no spaces needed");
+        let registry = registry.unwrap();
+        assert_eq!(registry.get(6, Part::One, 0xaf), Some("288"));
+        assert_eq!(registry.get(6, Part::Two, 0xaf), Some("71503"));
+        assert_eq!(registry.get(7, Part::One, 0xaf63dc4c8601ec8c), Some("no spaces needed"));
+        assert_eq!(registry.get(7, Part::Two, 0xaf63dc4c8601ec8c), None);
+
+        let registry = AnswerRegistry::parse("6 1 00000000000000af 288\n6 3 00000000000000af 1");
+        assert_eq!(registry, Err(RegistryError { line: 2, reason: String::from("Part must be 1 or 2!") }));
+
+        let registry = AnswerRegistry::parse("6 1 xyz 288");
+        assert_eq!(registry.map_err(|error| error.line), Err(1));
+
+        let registry = AnswerRegistry::parse("6 1 00000000000000af");
+        assert_eq!(registry.map_err(|error| error.reason), Err(String::from("Answer is missing!")));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut registry = AnswerRegistry::default();
+        assert!(registry.insert(7, Part::Two, 0xaf, "5905"));
+        assert!(registry.insert(6, Part::One, 0xaf, "288"));
+        assert!(!registry.insert(6, Part::One, 0xaf, "289"));
+        assert_eq!(registry.to_string(), "# day part input-hash answer
+6 1 00000000000000af 288
+7 2 00000000000000af 5905
+");
+        assert_eq!(AnswerRegistry::parse(&registry.to_string()), Ok(registry));
+    }
+
+    #[test]
+    fn test_verify() {
+        let registry = AnswerRegistry::parse("6 1 00000000000000af 288").unwrap();
+        let answer = Answer { part: Part::One, value: Some(String::from("288")), duration: Duration::ZERO, warnings: vec![] };
+        assert_eq!(registry.verify(6, 0xaf, &answer), Verdict::Pass);
+        assert_eq!(registry.verify(6, 0xb0, &answer), Verdict::Missing);
+
+        let answer = Answer { part: Part::One, value: Some(String::from("289")), duration: Duration::ZERO, warnings: vec![] };
+        assert_eq!(registry.verify(6, 0xaf, &answer), Verdict::Fail {
+            expected: String::from("288"),
+            actual: Some(String::from("289"))
+        });
+
+        let answer = Answer { part: Part::Two, value: None, duration: Duration::ZERO, warnings: vec![] };
+        assert_eq!(registry.verify(6, 0xaf, &answer), Verdict::Missing);
+    }
+}
\ No newline at end of file
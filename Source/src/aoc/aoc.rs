@@ -0,0 +1,270 @@
+// Runs any of the Advent of Code 2023 solutions.
+
+use std::{env, fs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use adventofcode_2023::answers::{self, Verdict};
+use adventofcode_2023::bench::{Baseline, DayBenchmark};
+use adventofcode_2023::generator;
+use adventofcode_2023::cli::{self, Arguments, CliError, Format};
+use adventofcode_2023::solution::Parts;
+
+const USAGE: &str = "aoc [-v|-vv|-q] list
+\taoc [-v|-vv|-q] run --day <day> [--part 1|2|both] [--format text|json] [file paths, directories or - for stdin]
+\taoc [-v|-vv|-q] all [--part 1|2|both] [--format text|json] [inputs directory]
+\taoc [-v|-vv|-q] verify [--answers <file path>] [inputs directory]
+\taoc [-v|-vv|-q] record [--answers <file path>] [inputs directory]
+\taoc [-v|-vv|-q] bench [--runs <count>] [--threshold <percent>] [--baseline <file path>] [--update-baseline] [inputs directory]
+\taoc [-v|-vv|-q] generate --day <day> [--seed <seed>] [--size <size>]";
+
+fn main() {
+    if let Err(error) = run() {
+        cli::exit_with_error(error);
+    }
+}
+
+fn run() -> Result<(), CliError> {
+    let mut args = env::args().skip(1);
+    let command = args.next();
+    let option_names = ["--day", "--part", "--format", "--answers", "--runs", "--threshold", "--baseline", "--seed", "--size"];
+    let arguments = Arguments::parse_with_flags(args, &option_names, &["--update-baseline"], USAGE)?;
+    cli::init_logging(arguments.log_level());
+
+    match command.as_deref() {
+        Some("list") => list(),
+        Some("run") => run_day(&arguments),
+        Some("all") => run_all(&arguments),
+        Some("verify") => verify(&arguments),
+        Some("record") => record(&arguments),
+        Some("bench") => bench(&arguments),
+        Some("generate") => generate(&arguments),
+        _ => Err(CliError::InvalidFormat(String::from(USAGE)))
+    }
+}
+
+fn list() -> Result<(), CliError> {
+    for solver in adventofcode_2023::solvers() {
+        println!("Day {}: {}", solver.day(), solver.title());
+    }
+
+    Ok(())
+}
+
+fn run_day(arguments: &Arguments) -> Result<(), CliError> {
+    let parts = arguments.parts()?;
+    let format = arguments.format()?;
+    let day = arguments
+        .option("--day")
+        .ok_or_else(|| CliError::InvalidFormat(String::from(USAGE)))?;
+    let solver = day
+        .parse::<u8>()
+        .ok()
+        .and_then(adventofcode_2023::find_solver)
+        .ok_or_else(|| CliError::UnknownDay(String::from(day)))?;
+    let file_paths = cli::resolve_inputs(solver.day(), arguments.positional())?;
+
+    cli::solve_files(solver.as_ref(), &file_paths, parts, format)
+}
+
+fn run_all(arguments: &Arguments) -> Result<(), CliError> {
+    let parts = arguments.parts()?;
+    let format = arguments.format()?;
+    let inputs_directory = inputs_directory(arguments)?;
+
+    let mut failed = 0;
+
+    for solver in adventofcode_2023::solvers() {
+        let file_paths = match cli::day_inputs(inputs_directory, solver.day()) {
+            Ok(file_paths) => file_paths,
+            Err(error) => {
+                if format == Format::Text {
+                    println!("Day {}: {}", solver.day(), solver.title());
+                }
+                eprintln!("{error}");
+                failed += 1;
+                continue;
+            }
+        };
+
+        for file_path in file_paths {
+            match cli::solve(solver.as_ref(), &file_path, parts) {
+                Ok(answers) => cli::print_answers(solver.as_ref(), &file_path, &answers, format),
+                Err(error) => {
+                    eprintln!("{error}");
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(CliError::FailedInputs(failed))
+    }
+}
+
+fn verify(arguments: &Arguments) -> Result<(), CliError> {
+    let inputs_directory = inputs_directory(arguments)?;
+    let answers_path = answers_path(arguments, inputs_directory);
+    let registry = cli::read_answers(&answers_path)?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+
+    for solver in adventofcode_2023::solvers() {
+        for file_path in cli::day_inputs(inputs_directory, solver.day()).unwrap_or_default() {
+            let input = cli::read_input(&file_path)?;
+            let input_hash = answers::input_hash(&input);
+            let answers = match cli::solve_input(solver.as_ref(), &file_path, &input, Parts::Both) {
+                Ok(answers) => answers,
+                Err(error) => {
+                    eprintln!("{error}");
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            for answer in answers {
+                let day = solver.day();
+                let part = answer.part.number();
+                let file_path = file_path.display();
+                match registry.verify(day, input_hash, &answer) {
+                    Verdict::Pass => {
+                        passed += 1;
+                        println!("Day {day} part {part} ({file_path}): pass");
+                    }
+                    Verdict::Fail { expected, actual } => {
+                        failed += 1;
+                        let actual = actual.unwrap_or_else(|| String::from("no answer"));
+                        println!("Day {day} part {part} ({file_path}): FAIL (expected {expected}, got {actual})");
+                    }
+                    Verdict::Missing => {
+                        missing += 1;
+                        println!("Day {day} part {part} ({file_path}): missing");
+                    }
+                }
+            }
+        }
+    }
+
+    println!("{passed} passed, {failed} failed, {missing} missing");
+
+    if failed > 0 {
+        Err(CliError::FailedVerification(failed))
+    } else {
+        Ok(())
+    }
+}
+
+fn record(arguments: &Arguments) -> Result<(), CliError> {
+    let inputs_directory = inputs_directory(arguments)?;
+    let answers_path = answers_path(arguments, inputs_directory);
+    let mut registry = cli::read_answers(&answers_path)?;
+
+    for solver in adventofcode_2023::solvers() {
+        for file_path in cli::day_inputs(inputs_directory, solver.day()).unwrap_or_default() {
+            let input = cli::read_input(&file_path)?;
+            let input_hash = answers::input_hash(&input);
+
+            for answer in cli::solve_input(solver.as_ref(), &file_path, &input, Parts::Both)? {
+                let day = solver.day();
+                let part = answer.part.number();
+                let file_path = file_path.display();
+                match answer.value {
+                    None => println!("Day {day} part {part} ({file_path}): no answer"),
+                    Some(value) => if registry.insert(day, answer.part, input_hash, &value) {
+                        println!("Day {day} part {part} ({file_path}): recorded {value}");
+                    } else {
+                        println!("Day {day} part {part} ({file_path}): already recorded");
+                    }
+                }
+            }
+        }
+    }
+
+    cli::write_answers(&answers_path, &registry)
+}
+
+fn bench(arguments: &Arguments) -> Result<(), CliError> {
+    let runs = arguments.parsed_option::<usize>("--runs", 10)?;
+    let threshold = arguments.parsed_option::<f64>("--threshold", 10.0)?;
+    let inputs_directory = inputs_directory(arguments)?;
+    let baseline_path = match arguments.option("--baseline") {
+        None => inputs_directory.join("bench.json"),
+        Some(baseline_path) => PathBuf::from(baseline_path)
+    };
+    let mut benchmarks = Vec::<DayBenchmark>::new();
+
+    for solver in adventofcode_2023::solvers() {
+        for file_path in cli::day_inputs(inputs_directory, solver.day()).unwrap_or_default() {
+            let input = cli::read_input(&file_path)?;
+            let input_name = file_path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let benchmark = DayBenchmark::run(solver.as_ref(), &input_name, &input, runs)
+                .map_err(|error| CliError::InvalidInput(file_path.clone(), error))?;
+            println!("Day {} ({}): parse {:?}, part 1 {:?}, part 2 {:?}", benchmark.day, file_path.display(),
+                     Duration::from_nanos(benchmark.parse_ns),
+                     Duration::from_nanos(benchmark.part1_ns),
+                     Duration::from_nanos(benchmark.part2_ns));
+            benchmarks.push(benchmark);
+        }
+    }
+
+    if arguments.flag("--update-baseline") || !baseline_path.exists() {
+        cli::write_file(&baseline_path, &Baseline { days: benchmarks }.to_json())?;
+        println!("Saved baseline to {}", baseline_path.display());
+        return Ok(());
+    }
+
+    let baseline = fs::read_to_string(&baseline_path)
+        .map_err(|error| CliError::UnreadableInput(baseline_path.clone(), error))?;
+    let baseline = Baseline::from_json(&baseline)
+        .map_err(|error| CliError::InvalidBaseline(baseline_path.clone(), error))?;
+    let regressions = baseline.regressions(&benchmarks, threshold);
+
+    for regression in &regressions {
+        println!("Day {} {} ({}): {:?} -> {:?} (+{:.1}%)", regression.day, regression.phase.name(), regression.input,
+                 Duration::from_nanos(regression.baseline_ns),
+                 Duration::from_nanos(regression.current_ns),
+                 regression.change_percent());
+    }
+
+    if regressions.is_empty() {
+        println!("No regressions beyond {threshold}% compared to {}", baseline_path.display());
+        Ok(())
+    } else {
+        Err(CliError::Regressions(regressions.len()))
+    }
+}
+
+fn generate(arguments: &Arguments) -> Result<(), CliError> {
+    let day = arguments
+        .option("--day")
+        .ok_or_else(|| CliError::InvalidFormat(String::from(USAGE)))?;
+    let seed = arguments.parsed_option::<u64>("--seed", 0)?;
+    let size = arguments.parsed_option::<usize>("--size", 100)?;
+    let input = day
+        .parse::<u8>()
+        .ok()
+        .and_then(|day| generator::generate(day, seed, size))
+        .ok_or_else(|| CliError::UnknownDay(String::from(day)))?;
+
+    print!("{input}");
+    Ok(())
+}
+
+fn inputs_directory(arguments: &Arguments) -> Result<&Path, CliError> {
+    match arguments.positional() {
+        [] => Ok(Path::new(cli::INPUTS_DIRECTORY)),
+        [inputs_directory] => Ok(Path::new(inputs_directory)),
+        _ => Err(CliError::InvalidFormat(String::from(USAGE)))
+    }
+}
+
+fn answers_path(arguments: &Arguments, inputs_directory: &Path) -> PathBuf {
+    match arguments.option("--answers") {
+        None => inputs_directory.join("answers.txt"),
+        Some(answers_path) => PathBuf::from(answers_path)
+    }
+}
@@ -0,0 +1,193 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::parse_error::ParseError;
+use crate::solution::{Solver, Timing};
+
+/// Median timings of a day over several runs, in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayBenchmark {
+    pub day: u8,
+    /// File name of the input within its day directory, so each input is compared to itself.
+    pub input: String,
+    pub runs: usize,
+    pub parse_ns: u64,
+    pub part1_ns: u64,
+    pub part2_ns: u64
+}
+
+/// Benchmark results saved to compare later runs against.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub days: Vec<DayBenchmark>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub input: String,
+    pub phase: Phase,
+    pub baseline_ns: u64,
+    pub current_ns: u64
+}
+
+impl DayBenchmark {
+    /// Runs `solver` on `input`, read from the file `input_name`, `runs` times and keeps the median of each phase.
+    pub fn run(solver: &dyn Solver, input_name: &str, input: &str, runs: usize) -> Result<Self, ParseError> {
+        let timings = (0..runs.max(1))
+            .map(|_| solver.time(input))
+            .collect::<Result<Vec<Timing>, ParseError>>()?;
+
+        Ok(Self {
+            day: solver.day(),
+            input: String::from(input_name),
+            runs: timings.len(),
+            parse_ns: median(timings.iter().map(|timing| timing.parse)),
+            part1_ns: median(timings.iter().map(|timing| timing.part1)),
+            part2_ns: median(timings.iter().map(|timing| timing.part2))
+        })
+    }
+
+    pub fn phase_ns(&self, phase: Phase) -> u64 {
+        match phase {
+            Phase::Parse => self.parse_ns,
+            Phase::Part1 => self.part1_ns,
+            Phase::Part2 => self.part2_ns
+        }
+    }
+}
+
+impl Baseline {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn get(&self, day: u8, input: &str) -> Option<&DayBenchmark> {
+        self.days
+            .iter()
+            .find(|benchmark| benchmark.day == day && benchmark.input == input)
+    }
+
+    /// Phases of `current` that are more than `threshold_percent` slower than in the baseline.
+    ///
+    /// Inputs missing from the baseline are never regressions.
+    pub fn regressions(&self, current: &[DayBenchmark], threshold_percent: f64) -> Vec<Regression> {
+        let mut regressions = Vec::<Regression>::new();
+
+        for benchmark in current {
+            let baseline = match self.get(benchmark.day, &benchmark.input) {
+                None => continue,
+                Some(baseline) => baseline
+            };
+
+            for phase in [Phase::Parse, Phase::Part1, Phase::Part2] {
+                let baseline_ns = baseline.phase_ns(phase);
+                let current_ns = benchmark.phase_ns(phase);
+                if current_ns as f64 > baseline_ns as f64 * (1.0 + threshold_percent / 100.0) {
+                    regressions.push(Regression {
+                        day: benchmark.day,
+                        input: benchmark.input.clone(),
+                        phase,
+                        baseline_ns,
+                        current_ns
+                    });
+                }
+            }
+        }
+
+        regressions
+    }
+}
+
+impl Phase {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part 1",
+            Phase::Part2 => "part 2"
+        }
+    }
+}
+
+impl Regression {
+    pub fn change_percent(&self) -> f64 {
+        if self.baseline_ns == 0 {
+            return f64::INFINITY;
+        }
+
+        (self.current_ns as f64 / self.baseline_ns as f64 - 1.0) * 100.0
+    }
+}
+
+fn median(durations: impl Iterator<Item = Duration>) -> u64 {
+    let mut durations = durations
+        .map(|duration| duration.as_nanos() as u64)
+        .collect::<Vec<u64>>();
+    durations.sort();
+
+    match durations.len() {
+        0 => 0,
+        len if len % 2 == 0 => (durations[len / 2 - 1] + durations[len / 2]) / 2,
+        len => durations[len / 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wait_for_it::Day6;
+
+    fn benchmark(day: u8, parse_ns: u64, part1_ns: u64, part2_ns: u64) -> DayBenchmark {
+        DayBenchmark { day, input: String::from("input.txt"), runs: 5, parse_ns, part1_ns, part2_ns }
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median([3, 1, 2].into_iter().map(Duration::from_nanos)), 2);
+        assert_eq!(median([4, 1, 2, 3].into_iter().map(Duration::from_nanos)), 2);
+        assert_eq!(median(std::iter::empty()), 0);
+    }
+
+    #[test]
+    fn test_day_benchmark_run() {
+        let solver = crate::solution::solver::<Day6>();
+        let benchmark = DayBenchmark::run(solver.as_ref(), "input.txt", "Time: 7 15 30\nDistance: 9 40 200", 3).unwrap();
+        assert_eq!((benchmark.day, benchmark.input.as_str(), benchmark.runs), (6, "input.txt", 3));
+
+        let benchmark = DayBenchmark::run(solver.as_ref(), "input.txt", "Time: 7 15 30", 3);
+        assert!(benchmark.is_err());
+    }
+
+    #[test]
+    fn test_baseline_json() {
+        let baseline = Baseline { days: vec![benchmark(6, 100, 200, 300)] };
+        assert_eq!(Baseline::from_json(&baseline.to_json()).unwrap(), baseline);
+        assert!(Baseline::from_json("{\"days\": [{\"day\": 6}]}").is_err());
+    }
+
+    #[test]
+    fn test_baseline_regressions() {
+        let baseline = Baseline { days: vec![benchmark(6, 100, 200, 0), benchmark(7, 100, 100, 100)] };
+        let other_input = DayBenchmark { input: String::from("example.txt"), ..benchmark(7, 999, 999, 999) };
+        let current = vec![benchmark(6, 109, 300, 1), benchmark(7, 50, 100, 100), benchmark(8, 999, 999, 999), other_input];
+
+        let regressions = baseline.regressions(&current, 10.0);
+        assert_eq!(regressions, vec![
+            Regression { day: 6, input: String::from("input.txt"), phase: Phase::Part1, baseline_ns: 200, current_ns: 300 },
+            Regression { day: 6, input: String::from("input.txt"), phase: Phase::Part2, baseline_ns: 0, current_ns: 1 }
+        ]);
+        assert_eq!(regressions[0].change_percent(), 50.0);
+
+        assert_eq!(baseline.regressions(&current, 5.0).len(), 3);
+    }
+}
\ No newline at end of file
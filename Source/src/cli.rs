@@ -0,0 +1,540 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::io::{BufRead, Read};
+use std::{env, fs, io, process};
+use log::LevelFilter;
+use serde::Serialize;
+use crate::answers::{AnswerRegistry, RegistryError};
+use crate::parse_error::ParseError;
+use crate::solution::{Answer, Parts, Solver};
+
+/// Path standing for the standard input.
+pub const STDIN: &str = "-";
+/// Directory with the `dayN` input directories.
+pub const INPUTS_DIRECTORY: &str = "inputs";
+/// Extension of the files with the expected answers of an example fixture.
+pub const EXPECTED_EXTENSION: &str = "expected";
+
+#[derive(Debug)]
+pub enum CliError {
+    InvalidFormat(String),
+    UnknownDay(String),
+    InvalidPart(String),
+    MissingInput(PathBuf),
+    NoInputFiles(PathBuf),
+    UnreadableInput(PathBuf, io::Error),
+    InvalidInput(PathBuf, ParseError),
+    /// Every invalid line of an input that is checked as a whole.
+    InvalidLines(PathBuf, Vec<ParseError>),
+    InvalidAnswers(PathBuf, RegistryError),
+    InvalidBaseline(PathBuf, serde_json::Error),
+    InvalidOption(String, String),
+    UnwritableOutput(PathBuf, io::Error),
+    FailedVerification(usize),
+    Regressions(usize),
+    FailedInputs(usize)
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::InvalidFormat(usage) => write!(f, "Invalid format! Run it like this:\n\n\t{usage}"),
+            CliError::UnknownDay(day) => write!(f, "There is no solution for day {day}!"),
+            CliError::InvalidPart(part) => write!(f, "Invalid part {part}! Part must be 1, 2 or both."),
+            CliError::MissingInput(file_path) => write!(f, "Input file {} doesn't exist!", file_path.display()),
+            CliError::NoInputFiles(directory) => write!(f, "No input files found in {}!", directory.display()),
+            CliError::UnreadableInput(file_path, error) => write!(f, "Can't read input file {}: {error}", file_path.display()),
+            CliError::InvalidInput(file_path, error) => write!(f, "{}: {error}", file_path.display()),
+            CliError::InvalidLines(file_path, errors) => {
+                for error in errors {
+                    writeln!(f, "{}: {error}", file_path.display())?;
+                }
+                write!(f, "{} invalid line(s) in {}!", errors.len(), file_path.display())
+            }
+            CliError::InvalidAnswers(file_path, error) => write!(f, "{}: {error}", file_path.display()),
+            CliError::InvalidBaseline(file_path, error) => write!(f, "{}: Invalid baseline! {error}", file_path.display()),
+            CliError::InvalidOption(name, value) => write!(f, "Invalid value {value} for {name}!"),
+            CliError::UnwritableOutput(file_path, error) => write!(f, "Can't write file {}: {error}", file_path.display()),
+            CliError::FailedVerification(count) => write!(f, "{count} answer(s) don't match the registered ones!"),
+            CliError::Regressions(count) => write!(f, "{count} phase(s) regressed compared to the baseline!"),
+            CliError::FailedInputs(count) => write!(f, "{count} input(s) couldn't be solved!")
+        }
+    }
+}
+
+/// How answers are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    /// One JSON object per answer and line.
+    Json
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        match str {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown format {str}! Format must be text or json."))
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AnswerRecord<'a> {
+    day: u8,
+    title: &'a str,
+    input: String,
+    part: u8,
+    answer: Option<&'a str>,
+    time_ns: u64,
+    warnings: &'a [String]
+}
+
+/// Command line arguments split into positional arguments and `--name value` options.
+#[derive(Debug, PartialEq)]
+pub struct Arguments {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: HashSet<String>,
+    verbosity: i8
+}
+
+impl Arguments {
+    /// Parses `args`, where every name in `option_names` expects a value.
+    ///
+    /// `-v`, `-vv` and `-q` are accepted everywhere and raise or lower the log level.
+    pub fn parse(args: impl Iterator<Item = String>, option_names: &[&str], usage: &str) -> Result<Self, CliError> {
+        Self::parse_with_flags(args, option_names, &[], usage)
+    }
+
+    /// Parses `args` like [Arguments::parse], and also accepts the names in `flag_names` without a value.
+    pub fn parse_with_flags(args: impl Iterator<Item = String>, option_names: &[&str], flag_names: &[&str], usage: &str) -> Result<Self, CliError> {
+        let mut positional = Vec::<String>::new();
+        let mut options = HashMap::<String, String>::new();
+        let mut flags = HashSet::<String>::new();
+        let mut verbosity = 0;
+        let mut args = args;
+
+        while let Some(arg) = args.next() {
+            if let Some(change) = verbosity_change(&arg) {
+                verbosity += change;
+            } else if flag_names.contains(&arg.as_str()) {
+                flags.insert(arg);
+            } else if option_names.contains(&arg.as_str()) {
+                match args.next() {
+                    None => return Err(CliError::InvalidFormat(String::from(usage))),
+                    Some(value) => options.insert(arg, value)
+                };
+            } else if arg.starts_with("--") {
+                return Err(CliError::InvalidFormat(String::from(usage)));
+            } else {
+                positional.push(arg);
+            }
+        }
+
+        Ok(Self { positional, options, flags, verbosity })
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    /// Parses the value of the option `name`, or returns `default` if it's missing.
+    pub fn parsed_option<T: FromStr>(&self, name: &str, default: T) -> Result<T, CliError> {
+        match self.option(name) {
+            None => Ok(default),
+            Some(value) => value
+                .parse::<T>()
+                .map_err(|_| CliError::InvalidOption(String::from(name), String::from(value)))
+        }
+    }
+
+    pub fn parts(&self) -> Result<Parts, CliError> {
+        match self.option("--part") {
+            None => Ok(Parts::Both),
+            Some(part) => part.parse::<Parts>().map_err(|_| CliError::InvalidPart(String::from(part)))
+        }
+    }
+
+    pub fn format(&self) -> Result<Format, CliError> {
+        self.parsed_option("--format", Format::Text)
+    }
+
+    /// Warnings by default, errors only with `-q`, debug events with `-v` and everything with `-vv`.
+    pub fn log_level(&self) -> LevelFilter {
+        match self.verbosity {
+            ..=-1 => LevelFilter::Error,
+            0 => LevelFilter::Warn,
+            1 => LevelFilter::Debug,
+            2.. => LevelFilter::Trace
+        }
+    }
+}
+
+fn verbosity_change(arg: &str) -> Option<i8> {
+    match arg {
+        "-q" | "--quiet" => Some(-1),
+        "-v" | "--verbose" => Some(1),
+        "-vv" => Some(2),
+        _ => None
+    }
+}
+
+/// Logs the library's events to the standard error, `RUST_LOG` can still override the level per module.
+pub fn init_logging(level: LevelFilter) {
+    env_logger::Builder::new()
+        .filter_level(level)
+        .format_timestamp(None)
+        .parse_default_env()
+        .init();
+}
+
+/// Reads an input file, `-` reads the standard input.
+pub fn read_input(file_path: &Path) -> Result<String, CliError> {
+    if file_path == Path::new(STDIN) {
+        let mut input = String::new();
+        return io::stdin()
+            .read_to_string(&mut input)
+            .map(|_| input)
+            .map_err(|error| CliError::UnreadableInput(file_path.to_path_buf(), error));
+    }
+
+    if !file_path.exists() {
+        return Err(CliError::MissingInput(file_path.to_path_buf()));
+    }
+
+    fs::read_to_string(file_path).map_err(|error| CliError::UnreadableInput(file_path.to_path_buf(), error))
+}
+
+/// Opens an input file for buffered reading, `-` reads the standard input.
+pub fn open_input(file_path: &Path) -> Result<Box<dyn BufRead>, CliError> {
+    if file_path == Path::new(STDIN) {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+
+    if !file_path.exists() {
+        return Err(CliError::MissingInput(file_path.to_path_buf()));
+    }
+
+    fs::File::open(file_path)
+        .map(|file| Box::new(io::BufReader::new(file)) as Box<dyn BufRead>)
+        .map_err(|error| CliError::UnreadableInput(file_path.to_path_buf(), error))
+}
+
+/// Resolves the positional arguments of a day into input files.
+///
+/// `-` stands for the standard input, directories expand to the files they contain and without
+/// any arguments the files in `inputs/dayN/` are used.
+pub fn resolve_inputs(day: u8, paths: &[String]) -> Result<Vec<PathBuf>, CliError> {
+    if paths.is_empty() {
+        return day_inputs(Path::new(INPUTS_DIRECTORY), day);
+    }
+
+    let mut file_paths = Vec::<PathBuf>::new();
+
+    for path in paths.iter().map(PathBuf::from) {
+        if path.is_dir() {
+            file_paths.extend(directory_inputs(&path)?);
+        } else {
+            file_paths.push(path);
+        }
+    }
+
+    Ok(file_paths)
+}
+
+/// Input files of `day` in `<inputs directory>/dayN/`, sorted by name.
+///
+/// Hidden files and the expected answers of example fixtures (`*.expected`) aren't inputs.
+pub fn day_inputs(inputs_directory: &Path, day: u8) -> Result<Vec<PathBuf>, CliError> {
+    directory_inputs(&inputs_directory.join(format!("day{day}")))
+}
+
+fn directory_inputs(directory: &Path) -> Result<Vec<PathBuf>, CliError> {
+    if !directory.is_dir() {
+        return Err(CliError::NoInputFiles(directory.to_path_buf()));
+    }
+
+    let mut file_paths = fs::read_dir(directory)
+        .map_err(|error| CliError::UnreadableInput(directory.to_path_buf(), error))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_none_or(|extension| extension != EXPECTED_EXTENSION))
+        .collect::<Vec<PathBuf>>();
+    file_paths.sort();
+
+    if file_paths.is_empty() {
+        return Err(CliError::NoInputFiles(directory.to_path_buf()));
+    }
+
+    Ok(file_paths)
+}
+
+/// Solves and prints every input file, carrying on past inputs that fail.
+pub fn solve_files(solver: &dyn Solver, file_paths: &[PathBuf], parts: Parts, format: Format) -> Result<(), CliError> {
+    solve_files_with(solver, file_paths, format, |file_path| solve(solver, file_path, parts))
+}
+
+/// Prints the answers `solve` finds for each file, for binaries that solve inputs their own way.
+pub fn solve_files_with<F>(solver: &dyn Solver, file_paths: &[PathBuf], format: Format, mut solve: F) -> Result<(), CliError>
+where F: FnMut(&Path) -> Result<Vec<Answer>, CliError> {
+    for_each_input(file_paths, |file_path| {
+        let answers = solve(file_path)?;
+        print_answers(solver, file_path, &answers, format);
+        Ok(())
+    })
+}
+
+/// Runs `process` on each file.
+///
+/// A single file fails with its own error, several files report every error and fail at the end.
+pub fn for_each_input<F>(file_paths: &[PathBuf], mut process: F) -> Result<(), CliError>
+where F: FnMut(&Path) -> Result<(), CliError> {
+    let mut errors = Vec::<CliError>::new();
+
+    for file_path in file_paths {
+        if let Err(error) = process(file_path) {
+            errors.push(error);
+        }
+    }
+
+    if file_paths.len() == 1 {
+        return match errors.pop() {
+            None => Ok(()),
+            Some(error) => Err(error)
+        };
+    }
+
+    for error in &errors {
+        eprintln!("{error}");
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::FailedInputs(errors.len()))
+    }
+}
+
+/// Name of an input in the output, `stdin` for `-`.
+pub fn input_name(file_path: &Path) -> String {
+    if file_path == Path::new(STDIN) {
+        String::from("stdin")
+    } else {
+        file_path.display().to_string()
+    }
+}
+
+pub fn solve(solver: &dyn Solver, file_path: &Path, parts: Parts) -> Result<Vec<Answer>, CliError> {
+    let input = read_input(file_path)?;
+    solve_input(solver, file_path, &input, parts)
+}
+
+/// Solves `input`, which was read from `file_path`.
+pub fn solve_input(solver: &dyn Solver, file_path: &Path, input: &str, parts: Parts) -> Result<Vec<Answer>, CliError> {
+    solver
+        .solve(input, parts)
+        .map_err(|error| CliError::InvalidInput(file_path.to_path_buf(), error))
+}
+
+/// Loads the answer registry, a missing file is an empty registry.
+pub fn read_answers(file_path: &Path) -> Result<AnswerRegistry, CliError> {
+    if !file_path.exists() {
+        return Ok(AnswerRegistry::default());
+    }
+
+    let answers = fs::read_to_string(file_path).map_err(|error| CliError::UnreadableInput(file_path.to_path_buf(), error))?;
+    AnswerRegistry::parse(&answers).map_err(|error| CliError::InvalidAnswers(file_path.to_path_buf(), error))
+}
+
+pub fn write_answers(file_path: &Path, registry: &AnswerRegistry) -> Result<(), CliError> {
+    write_file(file_path, &registry.to_string())
+}
+
+pub fn write_file(file_path: &Path, contents: &str) -> Result<(), CliError> {
+    fs::write(file_path, contents).map_err(|error| CliError::UnwritableOutput(file_path.to_path_buf(), error))
+}
+
+pub fn print_answers(solver: &dyn Solver, file_path: &Path, answers: &[Answer], format: Format) {
+    match format {
+        Format::Text => print_text_answers(solver, file_path, answers),
+        Format::Json => for answer in answers {
+            println!("{}", answer_json(solver, file_path, answer));
+        }
+    }
+}
+
+fn print_text_answers(solver: &dyn Solver, file_path: &Path, answers: &[Answer]) {
+    println!("Day {}: {} ({})", solver.day(), solver.title(), input_name(file_path));
+
+    for answer in answers {
+        match &answer.value {
+            None => println!("Part {}: no answer", answer.part.number()),
+            Some(value) => println!("Part {}: {value}", answer.part.number())
+        }
+    }
+}
+
+fn answer_json(solver: &dyn Solver, file_path: &Path, answer: &Answer) -> String {
+    serde_json::to_string(&AnswerRecord {
+        day: solver.day(),
+        title: solver.title(),
+        input: input_name(file_path),
+        part: answer.part.number(),
+        answer: answer.value.as_deref(),
+        time_ns: answer.duration.as_nanos() as u64,
+        warnings: &answer.warnings
+    }).unwrap()
+}
+
+pub fn exit_with_error(error: CliError) -> ! {
+    eprintln!("{error}");
+    process::exit(1)
+}
+
+/// Entry point shared by the `dayN` binaries.
+pub fn run_day(day: u8) {
+    let usage = format!("day{day} [-v|-vv|-q] [--part 1|2|both] [--format text|json] [file paths, directories or - for stdin]");
+
+    if let Err(error) = try_run_day(day, &usage) {
+        exit_with_error(error);
+    }
+}
+
+fn try_run_day(day: u8, usage: &str) -> Result<(), CliError> {
+    let arguments = Arguments::parse(env::args().skip(1), &["--part", "--format"], usage)?;
+    init_logging(arguments.log_level());
+    let parts = arguments.parts()?;
+    let format = arguments.format()?;
+    let solver = crate::find_solver(day).ok_or_else(|| CliError::UnknownDay(day.to_string()))?;
+    let file_paths = resolve_inputs(day, arguments.positional())?;
+
+    solve_files(solver.as_ref(), &file_paths, parts, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::solution::Part;
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>().into_iter()
+    }
+
+    #[test]
+    fn test_answer_json() {
+        let solver = crate::find_solver(6).unwrap();
+        let answer = Answer {
+            part: Part::Two,
+            value: Some(String::from("71503")),
+            duration: Duration::from_micros(12),
+            warnings: vec![String::from("Suspicious \"input\"!")]
+        };
+        assert_eq!(answer_json(solver.as_ref(), Path::new("inputs/day6/input.txt"), &answer),
+                   r#"{"day":6,"title":"Wait For It","input":"inputs/day6/input.txt","part":2,"answer":"71503","time_ns":12000,"warnings":["Suspicious \"input\"!"]}"#);
+
+        let answer = Answer { part: Part::One, value: None, duration: Duration::ZERO, warnings: vec![] };
+        assert_eq!(answer_json(solver.as_ref(), Path::new(STDIN), &answer),
+                   r#"{"day":6,"title":"Wait For It","input":"stdin","part":1,"answer":null,"time_ns":0,"warnings":[]}"#);
+    }
+
+    #[test]
+    fn test_resolve_inputs() {
+        let directory = env::temp_dir().join(format!("aoc-resolve-inputs-{}", process::id()));
+        fs::create_dir_all(directory.join("day6")).unwrap();
+        fs::write(directory.join("day6").join("b.txt"), "").unwrap();
+        fs::write(directory.join("day6").join("a.txt"), "").unwrap();
+        fs::write(directory.join("day6").join(".gitkeep"), "").unwrap();
+        fs::write(directory.join("day6").join("a.expected"), "").unwrap();
+
+        let file_paths = resolve_inputs(6, &[String::from("x.txt"), String::from(STDIN), directory.join("day6").display().to_string()]);
+        assert_eq!(file_paths.unwrap(), vec![
+            PathBuf::from("x.txt"),
+            PathBuf::from(STDIN),
+            directory.join("day6").join("a.txt"),
+            directory.join("day6").join("b.txt")
+        ]);
+
+        assert_eq!(day_inputs(&directory, 6).unwrap().len(), 2);
+        assert!(matches!(day_inputs(&directory, 7), Err(CliError::NoInputFiles(_))));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_arguments_parse() {
+        let arguments = Arguments::parse(args(&["--day", "3", "input.txt", "--part", "2"]), &["--day", "--part"], "usage")
+            .unwrap();
+        assert_eq!(arguments.positional(), &[String::from("input.txt")]);
+        assert_eq!(arguments.option("--day"), Some("3"));
+        assert_eq!(arguments.parts().unwrap(), Parts::Two);
+
+        let arguments = Arguments::parse(args(&["input.txt"]), &["--part"], "usage").unwrap();
+        assert_eq!(arguments.parts().unwrap(), Parts::Both);
+
+        let arguments = Arguments::parse(args(&["input.txt", "--part"]), &["--part"], "usage");
+        assert!(matches!(arguments, Err(CliError::InvalidFormat(_))));
+
+        let arguments = Arguments::parse(args(&["--unknown", "input.txt"]), &["--part"], "usage");
+        assert!(matches!(arguments, Err(CliError::InvalidFormat(_))));
+
+        let arguments = Arguments::parse(args(&["--part", "3", "input.txt"]), &["--part"], "usage").unwrap();
+        assert!(matches!(arguments.parts(), Err(CliError::InvalidPart(_))));
+        assert_eq!(arguments.parsed_option::<u8>("--part", 1).unwrap(), 3);
+        assert_eq!(arguments.parsed_option::<u8>("--runs", 10).unwrap(), 10);
+        assert!(matches!(arguments.parsed_option::<bool>("--part", false), Err(CliError::InvalidOption(_, _))));
+
+        let arguments = Arguments::parse(args(&["--format", "json", "input.txt"]), &["--format"], "usage").unwrap();
+        assert_eq!(arguments.format().unwrap(), Format::Json);
+        assert_eq!(arguments.parsed_option::<Format>("--format", Format::Text).unwrap(), Format::Json);
+
+        let arguments = Arguments::parse(args(&["--format", "yaml", "input.txt"]), &["--format"], "usage").unwrap();
+        assert!(matches!(arguments.format(), Err(CliError::InvalidOption(_, _))));
+    }
+
+    #[test]
+    fn test_arguments_log_level() {
+        let arguments = Arguments::parse(args(&["input.txt", "-"]), &[], "usage").unwrap();
+        assert_eq!(arguments.log_level(), LevelFilter::Warn);
+        assert_eq!(arguments.positional(), &[String::from("input.txt"), String::from("-")]);
+
+        let arguments = Arguments::parse(args(&["-v", "input.txt"]), &[], "usage").unwrap();
+        assert_eq!(arguments.log_level(), LevelFilter::Debug);
+        assert_eq!(arguments.positional(), &[String::from("input.txt")]);
+
+        let arguments = Arguments::parse(args(&["-vv"]), &[], "usage").unwrap();
+        assert_eq!(arguments.log_level(), LevelFilter::Trace);
+
+        let arguments = Arguments::parse(args(&["--quiet"]), &[], "usage").unwrap();
+        assert_eq!(arguments.log_level(), LevelFilter::Error);
+    }
+
+    #[test]
+    fn test_arguments_flags() {
+        let arguments = Arguments::parse_with_flags(args(&["--strict", "input.txt"]), &["--mode"], &["--strict"], "usage")
+            .unwrap();
+        assert!(arguments.flag("--strict"));
+        assert_eq!(arguments.positional(), &[String::from("input.txt")]);
+
+        let arguments = Arguments::parse_with_flags(args(&["input.txt"]), &[], &["--strict"], "usage").unwrap();
+        assert!(!arguments.flag("--strict"));
+
+        let arguments = Arguments::parse(args(&["--strict"]), &[], "usage");
+        assert!(matches!(arguments, Err(CliError::InvalidFormat(_))));
+    }
+}
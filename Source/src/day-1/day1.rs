@@ -1,41 +1,133 @@
 // https://adventofcode.com/2023/day/1
 
 use std::env;
-use std::fs;
-use std::path;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::time::Instant;
+use adventofcode_2023::cli::{self, Arguments, CliError};
+use adventofcode_2023::{ParseError, Solution};
+use adventofcode_2023::solution::{self, Answer, Part};
+use adventofcode_2023::trebuchet::{self, CalibratedLine, CalibrationMode, Day1, DigitVocabulary};
 
-mod trebuchet;
+const USAGE: &str = "day1 [-v|-vv|-q] [--mode numeric|words|both | --part 1|2|both] [--strict] [--explain] [--vocabulary <file path>] [--format text|json] [file paths, directories or - for stdin]";
 
 fn main() {
-    let mut command_args = env::args();
-    _ = command_args.next();
-    let first_arg = command_args.next();
+    if let Err(error) = run() {
+        cli::exit_with_error(error);
+    }
+}
+
+fn run() -> Result<(), CliError> {
+    let arguments = Arguments::parse_with_flags(env::args().skip(1), &["--mode", "--part", "--vocabulary", "--format"], &["--strict", "--explain"], USAGE)?;
+    cli::init_logging(arguments.log_level());
+    let format = arguments.format()?;
+    let options = CalibrationOptions {
+        modes: modes(&arguments)?,
+        vocabulary: vocabulary(&arguments)?,
+        strict: arguments.flag("--strict"),
+        explain: arguments.flag("--explain")
+    };
+    let solver = solution::solver::<Day1>();
+    let file_paths = cli::resolve_inputs(Day1::DAY, arguments.positional())?;
+
+    cli::solve_files_with(solver.as_ref(), &file_paths, format, |file_path| calibrate(file_path, &options))
+}
+
+struct CalibrationOptions {
+    modes: Vec<CalibrationMode>,
+    vocabulary: DigitVocabulary,
+    strict: bool,
+    /// Prints why each line got its value to stderr, so it doesn't mix with the answers.
+    explain: bool
+}
+
+/// Modes picked by `--mode`, or by `--part` with part 1 being the numeric mode.
+fn modes(arguments: &Arguments) -> Result<Vec<CalibrationMode>, CliError> {
+    if arguments.option("--part").is_some() {
+        if arguments.option("--mode").is_some() {
+            return Err(CliError::InvalidFormat(String::from(USAGE)));
+        }
 
-    if let Some(file_path) = first_arg {
-        let file_path = path::Path::new(&file_path);
+        let parts = arguments.parts()?;
+        return Ok([CalibrationMode::Numeric, CalibrationMode::NumericAndWords]
+            .into_iter()
+            .filter(|mode| parts.contains(part(*mode)))
+            .collect::<Vec<CalibrationMode>>());
+    }
+
+    match arguments.option("--mode") {
+        None | Some("both") => Ok(vec![CalibrationMode::Numeric, CalibrationMode::NumericAndWords]),
+        Some(mode) => mode
+            .parse::<CalibrationMode>()
+            .map(|mode| vec![mode])
+            .map_err(|_| CliError::InvalidOption(String::from("--mode"), String::from(mode)))
+    }
+}
 
-        if !file_path.exists() {
-            eprintln!("Input file {} doesn't exist!", file_path.display());
-            return;
+fn vocabulary(arguments: &Arguments) -> Result<DigitVocabulary, CliError> {
+    match arguments.option("--vocabulary") {
+        None => Ok(trebuchet::default_vocabulary().clone()),
+        Some(file_path) => {
+            let file_path = Path::new(file_path);
+            DigitVocabulary::parse(&cli::read_input(file_path)?)
+                .map_err(|error| CliError::InvalidInput(file_path.to_path_buf(), error))
         }
+    }
+}
+
+/// Streams the file line by line and sums the calibration values of every requested mode.
+///
+/// In strict mode, lines without two digits are errors and all of them are reported, once per failing mode.
+fn calibrate(file_path: &Path, options: &CalibrationOptions) -> Result<Vec<Answer>, CliError> {
+    let CalibrationOptions { modes, vocabulary, strict, explain } = options;
+    let highlight = io::stderr().is_terminal();
+    let start = Instant::now();
+    let mut totals = vec![0u64; modes.len()];
+    let mut errors = Vec::<ParseError>::new();
+
+    for (index, calibrated) in vocabulary.scanner().calibrations(cli::open_input(file_path)?).enumerate() {
+        let CalibratedLine { line, calibration } = calibrated
+            .map_err(|error| CliError::UnreadableInput(file_path.to_path_buf(), error))?;
 
-        let file_content = fs::read_to_string(&file_path);
-
-        match file_content {
-            Ok(file_content) => {
-                let calibration_result: u32 = file_content
-                    .lines()
-                    .map(|line| {
-                        let calibration_value = trebuchet::extract_calibration_value(line);
-                        println!("{line} -> {calibration_value:?}");
-                        calibration_value
-                    })
-                    .sum();
-                println!("Calibration result is {calibration_result}.");
+        let line = String::from_utf8_lossy(&line);
+        if *explain {
+            for mode in modes {
+                eprintln!("{}:{} {}", file_path.display(), index + 1, mode.name());
+                eprintln!("{}", vocabulary.explain(&line, *mode).render(highlight));
             }
-            Err(error) => eprintln!("Can't read input file {}: {error}", file_path.display()),
         }
-    } else {
-        eprintln!("Invalid format! Run day-1 like this:\n\n\tday1 <file path>");
+
+        if !strict {
+            for (total, mode) in totals.iter_mut().zip(modes) {
+                *total += calibration.value(*mode).value() as u64;
+            }
+            continue;
+        }
+
+        for (total, mode) in totals.iter_mut().zip(modes) {
+            match calibration.value(*mode).strict(*mode, &line) {
+                Ok(value) => *total += value as u64,
+                Err(error) => errors.push(error.on_line(index))
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(CliError::InvalidLines(file_path.to_path_buf(), errors));
+    }
+
+    // Every mode comes out of the same pass, so they all share its duration.
+    let duration = start.elapsed();
+    Ok(modes
+        .iter()
+        .zip(totals)
+        .map(|(mode, total)| Answer { part: part(*mode), value: Some(total.to_string()), duration, warnings: vec![] })
+        .collect::<Vec<Answer>>())
+}
+
+fn part(mode: CalibrationMode) -> Part {
+    match mode {
+        CalibrationMode::Numeric => Part::One,
+        CalibrationMode::NumericAndWords => Part::Two
     }
 }
\ No newline at end of file
@@ -1,75 +1,835 @@
-pub fn extract_calibration_value(line: &str) -> u32 {
-    let first_digit = get_first_digit(line);
-    let last_digit = get_last_digit(line);
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
+use std::io::{self, BufRead};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use crate::parse_error::ParseError;
+use crate::solution::Solution;
 
-    last_digit + first_digit * 10
+/// Which digits count in a calibration line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationMode {
+    /// Only the numerals of the vocabulary, 1 to 9 unless it adds 0, the rule of part 1.
+    Numeric,
+    /// The numerals and every name of the vocabulary, the rule of part 2.
+    NumericAndWords
 }
 
-fn get_first_digit(line: &str) -> u32 {
-    DIGITS
-        .iter()
-        .map(|digit| DigitPos::in_str(line, digit))
-        .filter_map(|digit_pos| digit_pos)
-        .min_by(DigitPos::compare_by_position)
-        .map(|digit_pos| digit_pos.digit.value)
-        .unwrap_or(0)
+impl CalibrationMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CalibrationMode::Numeric => "numeric",
+            CalibrationMode::NumericAndWords => "words"
+        }
+    }
+}
+
+impl FromStr for CalibrationMode {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        match str {
+            "numeric" => Ok(CalibrationMode::Numeric),
+            "words" => Ok(CalibrationMode::NumericAndWords),
+            _ => Err(format!("Unknown calibration mode {str}! Mode must be numeric or words."))
+        }
+    }
+}
+
+/// Calibration value of a line, with how many digits it was made of.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CalibrationValue {
+    /// The line has no digit, it counts as 0.
+    #[default]
+    NoDigit,
+    /// The line has a single digit, which is both the first and the last one.
+    SingleDigit(u32),
+    /// First digit times 10 plus last digit.
+    Normal(u32)
+}
+
+impl CalibrationValue {
+    pub fn value(&self) -> u32 {
+        match self {
+            CalibrationValue::NoDigit => 0,
+            CalibrationValue::SingleDigit(digit) => digit * 11,
+            CalibrationValue::Normal(value) => *value
+        }
+    }
+
+    /// The value in strict mode, where `line` must have at least two digits in `mode`.
+    ///
+    /// The error is located in `line`, use [ParseError::on_line] to move it to the line's place in the input.
+    pub fn strict(&self, mode: CalibrationMode, line: &str) -> Result<u32, ParseError> {
+        let reason = match self {
+            CalibrationValue::NoDigit => "no digit",
+            CalibrationValue::SingleDigit(_) => "a single digit",
+            CalibrationValue::Normal(value) => return Ok(*value)
+        };
+
+        Err(ParseError::new(Day1::DAY, line, line, format!("Line has {reason} in {} mode!", mode.name())))
+    }
+}
+
+/// Calibration values of a line in both modes, found in a single pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Calibration {
+    pub numeric: CalibrationValue,
+    pub numeric_and_words: CalibrationValue
+}
+
+impl Calibration {
+    pub fn value(&self, mode: CalibrationMode) -> CalibrationValue {
+        match mode {
+            CalibrationMode::Numeric => self.numeric,
+            CalibrationMode::NumericAndWords => self.numeric_and_words
+        }
+    }
+}
+
+pub fn extract_calibration_value(line: &str, mode: CalibrationMode, vocabulary: &DigitVocabulary) -> CalibrationValue {
+    vocabulary
+        .scanner()
+        .calibrate(line.as_bytes())
+        .value(mode)
+}
+
+/// The English vocabulary used by the puzzle, built on first use.
+pub fn default_vocabulary() -> &'static DigitVocabulary {
+    static VOCABULARY: OnceLock<DigitVocabulary> = OnceLock::new();
+    VOCABULARY.get_or_init(DigitVocabulary::default)
+}
+
+const NUMERALS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+const ENGLISH: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const GERMAN: [&str; 9] = ["eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun"];
+const FRENCH: [&str; 9] = ["un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Digit {
+    pub name: String,
+    pub value: u32
+}
+
+impl Digit {
+    /// Whether the name is written with ASCII digits, like "7", the only names counted in numeric mode.
+    pub fn is_numeral(&self) -> bool {
+        self.name.bytes().all(|byte| byte.is_ascii_digit())
+    }
+}
+
+/// Names that stand for digits in calibration lines.
+///
+/// The default is the puzzle's vocabulary: the numerals 1 to 9 and their English names.
+/// Vocabularies can also be loaded from text, one digit per line followed by all of its names,
+/// with an optional `case-insensitive` line:
+///
+/// ```text
+/// # value names
+/// case-insensitive
+/// 0 0 zero
+/// 1 1 one uno
+/// ```
+#[derive(Clone)]
+pub struct DigitVocabulary {
+    digits: Vec<Digit>,
+    case_insensitive: bool,
+    scanner: OnceLock<DigitScanner>
+}
+
+impl DigitVocabulary {
+    /// Vocabulary without any name, see [DigitVocabulary::with_name].
+    pub fn empty() -> Self {
+        Self { digits: Vec::<Digit>::new(), case_insensitive: false, scanner: OnceLock::new() }
+    }
+
+    /// The numerals 1 to 9.
+    pub fn numerals() -> Self {
+        Self::empty().with_names(NUMERALS)
+    }
+
+    /// The numerals 1 to 9 and `names` for the digits 1 to 9.
+    pub fn spelled(names: [&str; 9]) -> Self {
+        Self::numerals().with_names(names)
+    }
+
+    pub fn english() -> Self {
+        Self::spelled(ENGLISH)
+    }
+
+    pub fn german() -> Self {
+        Self::spelled(GERMAN)
+    }
+
+    pub fn french() -> Self {
+        Self::spelled(FRENCH)
+    }
+
+    /// Adds an alias `name` for the digit `value`, empty names are ignored.
+    pub fn with_name(mut self, name: &str, value: u32) -> Self {
+        if !name.is_empty() {
+            self.digits.push(Digit { name: String::from(name), value });
+            self.scanner = OnceLock::new();
+        }
+
+        self
+    }
+
+    /// Adds `names` for the digits 1 to 9.
+    pub fn with_names(self, names: [&str; 9]) -> Self {
+        names
+            .into_iter()
+            .zip(1..)
+            .fold(self, |vocabulary, (name, value)| vocabulary.with_name(name, value))
+    }
+
+    /// Adds the numeral 0 and `name` for it.
+    pub fn with_zero(self, name: &str) -> Self {
+        self
+            .with_name("0", 0)
+            .with_name(name, 0)
+    }
+
+    /// Ignores the case of letters when matching names, non-ASCII ones like the ü of "fünf" included.
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_insensitive = true;
+        self.scanner = OnceLock::new();
+        self
+    }
+
+    pub fn parse(str: &str) -> Result<Self, ParseError> {
+        let mut vocabulary = Self::empty();
+
+        for (index, line) in str.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line == "case-insensitive" {
+                vocabulary = vocabulary.case_insensitive();
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let value = fields.next().unwrap_or(line);
+            let value = value
+                .parse::<u32>()
+                .ok()
+                .filter(|value| *value <= 9)
+                .ok_or_else(|| ParseError::new(Day1::DAY, line, value, "Digit must be between 0 and 9!").on_line(index))?;
+
+            let names = fields.collect::<Vec<&str>>();
+            if names.is_empty() {
+                return Err(ParseError::new(Day1::DAY, line, line, "Digit must have at least one name!").on_line(index));
+            }
+
+            vocabulary = names
+                .into_iter()
+                .fold(vocabulary, |vocabulary, name| vocabulary.with_name(name, value));
+        }
+
+        Ok(vocabulary)
+    }
+
+    pub fn digits(&self) -> &[Digit] {
+        &self.digits
+    }
+
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    /// Explains the calibration value of `line` in `mode`: which digits were found and which were picked.
+    pub fn explain<'a>(&'a self, line: &'a str, mode: CalibrationMode) -> Explanation<'a> {
+        let scan = self.scanner().scan_candidates(line.as_bytes());
+        let (first, last) = match mode {
+            CalibrationMode::Numeric => (scan.first_numeral, scan.last_numeral),
+            CalibrationMode::NumericAndWords => (scan.first, scan.last)
+        };
+        let span = |found: DigitMatch| DigitSpan {
+            start: found.start,
+            end: found.start + found.length,
+            value: found.value,
+            entry: &self.digits[found.entry]
+        };
+
+        let mut candidates = scan.candidates
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|found| mode == CalibrationMode::NumericAndWords || self.digits[found.entry].is_numeral())
+            .map(span)
+            .collect::<Vec<DigitSpan>>();
+        candidates.sort_by_key(|candidate| (candidate.start, candidate.end));
+
+        Explanation {
+            line,
+            mode,
+            value: calibration_value(first, last),
+            first: first.map(span),
+            last: last.map(span),
+            candidates
+        }
+    }
+
+    /// Automaton matching the names, built on first use.
+    pub fn scanner(&self) -> &DigitScanner {
+        self.scanner.get_or_init(|| DigitScanner::new(self))
+    }
+}
+
+impl Default for DigitVocabulary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl Debug for DigitVocabulary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DigitVocabulary")
+            .field("digits", &self.digits)
+            .field("case_insensitive", &self.case_insensitive)
+            .finish()
+    }
+}
+
+impl PartialEq for DigitVocabulary {
+    fn eq(&self, other: &Self) -> bool {
+        self.digits == other.digits && self.case_insensitive == other.case_insensitive
+    }
+}
+
+/// Digit found in a line at the bytes `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigitSpan<'a> {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+    /// Vocabulary entry that matched.
+    pub entry: &'a Digit
+}
+
+impl DigitSpan<'_> {
+    pub fn overlaps(&self, other: &DigitSpan) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// Why a line got its calibration value, see [DigitVocabulary::explain].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation<'a> {
+    pub line: &'a str,
+    pub mode: CalibrationMode,
+    pub value: CalibrationValue,
+    pub first: Option<DigitSpan<'a>>,
+    pub last: Option<DigitSpan<'a>>,
+    /// Every digit found in the line, ordered by position, overlapping ones included.
+    pub candidates: Vec<DigitSpan<'a>>
+}
+
+impl Explanation<'_> {
+    /// Candidates that weren't picked although they overlap the first or the last digit, like "one" in "twone3".
+    pub fn overlapping(&self) -> Vec<&DigitSpan<'_>> {
+        let picked = [self.first, self.last]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<DigitSpan>>();
+
+        self.candidates
+            .iter()
+            .filter(|candidate| !picked.contains(candidate))
+            .filter(|candidate| picked.iter().any(|span| span.overlaps(candidate)))
+            .collect::<Vec<&DigitSpan>>()
+    }
+
+    /// The line and its value, followed by one line per picked or overlapping digit, marked with `^` below the line.
+    ///
+    /// With `highlight`, the first and last digits are also colored in the line with ANSI escape codes.
+    pub fn render(&self, highlight: bool) -> String {
+        let kind = match self.value {
+            CalibrationValue::NoDigit => " (no digit)",
+            CalibrationValue::SingleDigit(_) => " (single digit)",
+            CalibrationValue::Normal(_) => ""
+        };
+        let line = if highlight { self.highlighted_line() } else { String::from(self.line) };
+        let width = self.line.chars().count();
+        let mut rendered = format!("{line} -> {}{kind}", self.value.value());
+
+        let labeled = [("first", self.first), ("last", self.last)]
+            .into_iter()
+            .filter_map(|(label, span)| span.map(|span| (label, span)))
+            .chain(self.overlapping().into_iter().map(|span| ("overlapping", *span)));
+        for (label, span) in labeled {
+            let column = self.line[..span.start].chars().count();
+            let length = self.line[span.start..span.end].chars().count();
+            let markers = format!("{}{}", " ".repeat(column), "^".repeat(length));
+            rendered.push_str(&format!("\n{markers:width$}  {label} {}", self.describe(&span)));
+        }
+
+        rendered
+    }
+
+    fn describe(&self, span: &DigitSpan) -> String {
+        let text = &self.line[span.start..span.end];
+        let entry = if span.entry.name != text { format!(" via \"{}\"", span.entry.name) } else { String::new() };
+
+        format!("\"{text}\"{entry} = {} at {}..{}", span.value, span.start, span.end)
+    }
+
+    fn highlighted_line(&self) -> String {
+        let style = |index: usize| {
+            let within = |span: Option<DigitSpan>| span.is_some_and(|span| span.start <= index && index < span.end);
+            if within(self.last) {
+                Some(HIGHLIGHT_LAST)
+            } else if within(self.first) {
+                Some(HIGHLIGHT_FIRST)
+            } else {
+                None
+            }
+        };
+
+        let mut highlighted = String::new();
+        let mut current = None;
+        for (index, char) in self.line.char_indices() {
+            let next = style(index);
+            if next != current {
+                highlighted.push_str(next.unwrap_or(HIGHLIGHT_RESET));
+                current = next;
+            }
+            highlighted.push(char);
+        }
+        if current.is_some() {
+            highlighted.push_str(HIGHLIGHT_RESET);
+        }
+
+        highlighted
+    }
 }
 
-fn get_last_digit(line: &str) -> u32 {
-    DIGITS
-        .iter()
-        .map(|digit| DigitPos::r_in_str(line, digit))
-        .filter_map(|digit_pos| digit_pos)
-        .max_by(DigitPos::compare_by_position)
-        .map(|digit_pos| digit_pos.digit.value)
-        .unwrap_or(0)
+const HIGHLIGHT_FIRST: &str = "\x1b[1;32m";
+const HIGHLIGHT_LAST: &str = "\x1b[1;33m";
+const HIGHLIGHT_RESET: &str = "\x1b[0m";
+
+const NO_STATE: u32 = u32::MAX;
+
+/// Aho-Corasick automaton finding every digit name of a vocabulary in a single left-to-right pass over bytes.
+///
+/// Overlapping names are all reported, so "twone" yields 2 and then 1.
+#[derive(Clone)]
+pub struct DigitScanner {
+    /// `transitions[state][byte]` is the next state, with the failure links already folded in.
+    transitions: Vec<[u32; 256]>,
+    /// Length, value, vocabulary index and numeral flag of every name ending in a state, including the names
+    /// reached through failure links.
+    matches: Vec<Vec<(usize, u32, usize, bool)>>
 }
 
-const DIGITS: [Digit; 18] = [
-    Digit { name: "1", value: 1 },
-    Digit { name: "one", value: 1 },
-    Digit { name: "2", value: 2 },
-    Digit { name: "two", value: 2 },
-    Digit { name: "3", value: 3 },
-    Digit { name: "three", value: 3 },
-    Digit { name: "4", value: 4 },
-    Digit { name: "four", value: 4 },
-    Digit { name: "5", value: 5 },
-    Digit { name: "five", value: 5 },
-    Digit { name: "6", value: 6 },
-    Digit { name: "six", value: 6 },
-    Digit { name: "7", value: 7 },
-    Digit { name: "seven", value: 7 },
-    Digit { name: "8", value: 8 },
-    Digit { name: "eight", value: 8 },
-    Digit { name: "9", value: 9 },
-    Digit { name: "nine", value: 9 }
-];
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DigitMatch {
+    start: usize,
+    length: usize,
+    value: u32,
+    /// Index of the name in the vocabulary.
+    entry: usize
+}
 
-struct DigitPos<'a> {
-    digit: &'a Digit,
-    position: usize
+/// State of the scan of a single line.
+#[derive(Debug, Default)]
+struct LineScan {
+    state: usize,
+    position: usize,
+    first: Option<DigitMatch>,
+    last: Option<DigitMatch>,
+    first_numeral: Option<DigitMatch>,
+    last_numeral: Option<DigitMatch>,
+    /// Every match, only collected when explaining a line.
+    candidates: Option<Vec<DigitMatch>>
 }
 
-impl<'a, 'b> DigitPos<'a> {
-    fn in_str(str: &str, digit: &'a Digit) -> Option<DigitPos<'a>> {
-        str
-            .find(digit.name)
-            .map(|position| DigitPos { digit, position })
+impl DigitScanner {
+    pub fn new(vocabulary: &DigitVocabulary) -> Self {
+        let mut transitions = vec![[NO_STATE; 256]];
+        let mut matches = vec![Vec::<(usize, u32, usize, bool)>::new()];
+
+        for (entry, digit) in vocabulary.digits().iter().enumerate() {
+            let names = if vocabulary.case_insensitive { case_variants(&digit.name) } else { vec![digit.name.clone()] };
+            for name in names {
+                let mut state = 0;
+                for byte in name.bytes() {
+                    if transitions[state][byte as usize] == NO_STATE {
+                        transitions[state][byte as usize] = transitions.len() as u32;
+                        transitions.push([NO_STATE; 256]);
+                        matches.push(Vec::<(usize, u32, usize, bool)>::new());
+                    }
+                    state = transitions[state][byte as usize] as usize;
+                }
+                matches[state].push((name.len(), digit.value, entry, digit.is_numeral()));
+            }
+        }
+
+        // Breadth first, so the failure state of a state is always complete before the state itself.
+        let mut failures = vec![0; transitions.len()];
+        let mut queue = VecDeque::<usize>::from([0]);
+        while let Some(state) = queue.pop_front() {
+            let fallbacks = if state == 0 { [0; 256] } else { transitions[failures[state]] };
+
+            for (next, fallback) in transitions[state].iter_mut().zip(fallbacks) {
+                if *next == NO_STATE {
+                    *next = fallback;
+                } else {
+                    let next = *next as usize;
+                    failures[next] = fallback as usize;
+                    let inherited = matches[fallback as usize].clone();
+                    matches[next].extend(inherited);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if vocabulary.case_insensitive {
+            for transitions in &mut transitions {
+                for lowercase in b'a'..=b'z' {
+                    transitions[lowercase.to_ascii_uppercase() as usize] = transitions[lowercase as usize];
+                }
+            }
+        }
+
+        Self { transitions, matches }
     }
 
-    fn r_in_str(str: &str, digit: &'a Digit) -> Option<DigitPos<'a>> {
-        str
-            .rfind(digit.name)
-            .map(|position| DigitPos { digit, position })
+    /// Calibration value of `line` in both modes.
+    pub fn calibrate(&self, line: &[u8]) -> Calibration {
+        let mut scan = LineScan::default();
+        for &byte in line {
+            self.feed(&mut scan, byte);
+        }
+
+        scan.calibration()
+    }
+
+    /// Scans `line` and keeps every match.
+    fn scan_candidates(&self, line: &[u8]) -> LineScan {
+        let mut scan = LineScan { candidates: Some(Vec::<DigitMatch>::new()), ..LineScan::default() };
+        for &byte in line {
+            self.feed(&mut scan, byte);
+        }
+
+        scan
     }
 
-    fn compare_by_position(left: &DigitPos<'a>, right: &DigitPos<'b>) -> std::cmp::Ordering {
-        left.position.cmp(&right.position)
+    /// Calibration of every line read from `reader`, without holding more than a line of it in memory.
+    pub fn calibrations<R: BufRead>(&self, reader: R) -> Calibrations<'_, R> {
+        Calibrations { scanner: self, reader }
+    }
+
+    fn feed(&self, scan: &mut LineScan, byte: u8) {
+        scan.state = self.transitions[scan.state][byte as usize] as usize;
+        scan.position += 1;
+
+        for &(length, value, entry, numeral) in &self.matches[scan.state] {
+            let found = DigitMatch { start: scan.position - length, length, value, entry };
+            if let Some(candidates) = &mut scan.candidates {
+                candidates.push(found);
+            }
+            if numeral {
+                keep_first_and_last(&mut scan.first_numeral, &mut scan.last_numeral, found);
+            }
+            keep_first_and_last(&mut scan.first, &mut scan.last, found);
+        }
+    }
+}
+
+fn keep_first_and_last(first: &mut Option<DigitMatch>, last: &mut Option<DigitMatch>, found: DigitMatch) {
+    // Longer names win ties, as they contain the shorter ones.
+    if first.is_none_or(|first| (found.start, Reverse(found.length)) < (first.start, Reverse(first.length))) {
+        *first = Some(found);
+    }
+    if last.is_none_or(|last| (found.start, found.length) > (last.start, last.length)) {
+        *last = Some(found);
+    }
+}
+
+/// Every spelling of `name` in lowercase and uppercase, as non-ASCII letters can't be folded byte by byte.
+///
+/// ASCII letters are only spelled in lowercase, the scanner maps uppercase ASCII bytes onto them.
+fn case_variants(name: &str) -> Vec<String> {
+    name.chars().fold(vec![String::new()], |variants, char| {
+        let mut spellings = [char.to_string(), char.to_lowercase().to_string(), char.to_uppercase().to_string()]
+            .map(|spelling| spelling.to_ascii_lowercase())
+            .to_vec();
+        spellings.sort();
+        spellings.dedup();
+
+        variants
+            .iter()
+            .flat_map(|variant| spellings.iter().map(move |spelling| format!("{variant}{spelling}")))
+            .collect::<Vec<String>>()
+    })
+}
+
+impl Debug for DigitScanner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DigitScanner")
+            .field("states", &self.transitions.len())
+            .finish()
+    }
+}
+
+impl LineScan {
+    fn calibration(&self) -> Calibration {
+        Calibration {
+            numeric: calibration_value(self.first_numeral, self.last_numeral),
+            numeric_and_words: calibration_value(self.first, self.last)
+        }
     }
 }
 
-struct Digit {
-    name: &'static str,
-    value: u32
+fn calibration_value(first: Option<DigitMatch>, last: Option<DigitMatch>) -> CalibrationValue {
+    match (first, last) {
+        (Some(first), Some(last)) if first == last => CalibrationValue::SingleDigit(first.value),
+        (Some(first), Some(last)) => CalibrationValue::Normal(first.value * 10 + last.value),
+        _ => CalibrationValue::NoDigit
+    }
 }
+
+/// A line read by [Calibrations], without its line break, and its calibration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalibratedLine {
+    pub line: Vec<u8>,
+    pub calibration: Calibration
+}
+
+/// Iterator over the calibrations of the lines of a reader, see [DigitScanner::calibrations].
+pub struct Calibrations<'a, R> {
+    scanner: &'a DigitScanner,
+    reader: R
+}
+
+impl<R: BufRead> Iterator for Calibrations<'_, R> {
+    type Item = io::Result<CalibratedLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = Vec::<u8>::new();
+        match self.reader.read_until(b'\n', &mut line) {
+            Ok(0) => None,
+            Ok(_) => {
+                if line.ends_with(b"\n") {
+                    line.pop();
+                }
+                if line.ends_with(b"\r") {
+                    line.pop();
+                }
+
+                let calibration = self.scanner.calibrate(&line);
+                Some(Ok(CalibratedLine { line, calibration }))
+            }
+            Err(error) => Some(Err(error))
+        }
+    }
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Trebuchet?!";
+
+    type Input<'a> = Vec<Calibration>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    /// Both parts are computed while parsing, in a single pass over the lines.
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let scanner = default_vocabulary().scanner();
+        Ok(input
+            .lines()
+            .map(|line| scanner.calibrate(line.as_bytes()))
+            .collect::<Vec<Calibration>>())
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        Some(input.iter().map(|calibration| calibration.numeric.value()).sum())
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        Some(input.iter().map(|calibration| calibration.numeric_and_words.value()).sum())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_calibration_value() {
+        let vocabulary = DigitVocabulary::default();
+        assert_eq!(extract_calibration_value("two1nine", CalibrationMode::NumericAndWords, &vocabulary).value(), 29);
+        assert_eq!(extract_calibration_value("4nineeightseven2", CalibrationMode::NumericAndWords, &vocabulary).value(), 42);
+        assert_eq!(extract_calibration_value("7pqrstsixteen", CalibrationMode::NumericAndWords, &vocabulary).value(), 76);
+        assert_eq!(extract_calibration_value("treb7uchet", CalibrationMode::NumericAndWords, &vocabulary).value(), 77);
+        assert_eq!(extract_calibration_value("twone", CalibrationMode::NumericAndWords, &vocabulary).value(), 21);
+        assert_eq!(extract_calibration_value("eightwo", CalibrationMode::NumericAndWords, &vocabulary).value(), 82);
+        assert_eq!(extract_calibration_value("oneight", CalibrationMode::NumericAndWords, &vocabulary).value(), 18);
+        assert_eq!(extract_calibration_value("Nine0", CalibrationMode::NumericAndWords, &vocabulary).value(), 0);
+        assert_eq!(extract_calibration_value("", CalibrationMode::NumericAndWords, &vocabulary).value(), 0);
+    }
+
+    #[test]
+    fn test_calibration_modes() {
+        let vocabulary = DigitVocabulary::default();
+        assert_eq!(extract_calibration_value("two1nine", CalibrationMode::Numeric, &vocabulary).value(), 11);
+        assert_eq!(extract_calibration_value("pqr3stu8vwx", CalibrationMode::Numeric, &vocabulary).value(), 38);
+        assert_eq!(extract_calibration_value("a0b", CalibrationMode::Numeric, &vocabulary).value(), 0);
+        assert_eq!(extract_calibration_value("eightwothree", CalibrationMode::Numeric, &vocabulary).value(), 0);
+        assert_eq!(vocabulary.scanner().calibrate(b"seven4two"), Calibration {
+            numeric: CalibrationValue::SingleDigit(4),
+            numeric_and_words: CalibrationValue::Normal(72)
+        });
+
+        let vocabulary = DigitVocabulary::english().with_zero("zero");
+        assert_eq!(extract_calibration_value("a0b7", CalibrationMode::Numeric, &vocabulary), CalibrationValue::Normal(7));
+        let vocabulary = DigitVocabulary::empty().with_names(ENGLISH);
+        assert_eq!(extract_calibration_value("one2three", CalibrationMode::Numeric, &vocabulary), CalibrationValue::NoDigit);
+        assert_eq!(extract_calibration_value("one2three", CalibrationMode::NumericAndWords, &vocabulary).value(), 13);
+        assert_eq!("words".parse::<CalibrationMode>(), Ok(CalibrationMode::NumericAndWords));
+        assert!("both".parse::<CalibrationMode>().is_err());
+    }
+
+    #[test]
+    fn test_calibration_value() {
+        let vocabulary = DigitVocabulary::default();
+        let value = |line: &str| extract_calibration_value(line, CalibrationMode::NumericAndWords, &vocabulary);
+        assert_eq!(value("abc"), CalibrationValue::NoDigit);
+        assert_eq!(value("treb7uchet"), CalibrationValue::SingleDigit(7));
+        assert_eq!(value("77"), CalibrationValue::Normal(77));
+        assert_eq!(value("twone"), CalibrationValue::Normal(21));
+        assert_eq!(value("treb7uchet").value(), 77);
+
+        let words = CalibrationMode::NumericAndWords;
+        assert_eq!(value("a1b2").strict(words, "a1b2"), Ok(12));
+        let error = value("abc").strict(words, "abc").unwrap_err().on_line(2);
+        assert_eq!((error.line, error.reason), (3, String::from("Line has no digit in words mode!")));
+        let error = value("treb7uchet").strict(CalibrationMode::Numeric, "treb7uchet").unwrap_err();
+        assert_eq!(error.reason, String::from("Line has a single digit in numeric mode!"));
+    }
+
+    #[test]
+    fn test_explain() {
+        let vocabulary = DigitVocabulary::default();
+        let explanation = vocabulary.explain("oneight", CalibrationMode::NumericAndWords);
+        assert_eq!(explanation.value, CalibrationValue::Normal(18));
+        assert_eq!(explanation.first.map(|span| (span.start, span.end, span.value)), Some((0, 3, 1)));
+        assert_eq!(explanation.last.map(|span| (span.start, span.end, span.value)), Some((2, 7, 8)));
+        assert_eq!(explanation.last.map(|span| span.entry), Some(&Digit { name: String::from("eight"), value: 8 }));
+        assert!(explanation.overlapping().is_empty());
+        assert_eq!(explanation.render(false), "oneight -> 18
+^^^      first \"one\" = 1 at 0..3
+  ^^^^^  last \"eight\" = 8 at 2..7");
+
+        let explanation = vocabulary.explain("twone3", CalibrationMode::NumericAndWords);
+        assert_eq!(explanation.candidates.len(), 3);
+        assert_eq!(explanation.overlapping().iter().map(|span| span.value).collect::<Vec<u32>>(), vec![1]);
+        assert_eq!(explanation.render(false), "twone3 -> 23
+^^^     first \"two\" = 2 at 0..3
+     ^  last \"3\" = 3 at 5..6
+  ^^^   overlapping \"one\" = 1 at 2..5");
+        assert_eq!(explanation.render(true).lines().next(), Some("\x1b[1;32mtwo\x1b[0mne\x1b[1;33m3\x1b[0m -> 23"));
+
+        let explanation = vocabulary.explain("twone3", CalibrationMode::Numeric);
+        assert_eq!(explanation.value, CalibrationValue::SingleDigit(3));
+        assert_eq!(explanation.candidates.len(), 1);
+        assert_eq!(explanation.first.map(|span| span.entry.name.as_str()), Some("3"));
+
+        let vocabulary = DigitVocabulary::german().case_insensitive();
+        let explanation = vocabulary.explain("FÜNFfünf", CalibrationMode::NumericAndWords);
+        assert_eq!(explanation.render(false), "FÜNFfünf -> 55
+^^^^      first \"FÜNF\" via \"fünf\" = 5 at 0..5
+    ^^^^  last \"fünf\" = 5 at 5..10");
+        assert_eq!(vocabulary.explain("xEINSx", CalibrationMode::NumericAndWords).render(false), "xEINSx -> 11 (single digit)
+ ^^^^   first \"EINS\" via \"eins\" = 1 at 1..5
+ ^^^^   last \"EINS\" via \"eins\" = 1 at 1..5");
+        assert_eq!(vocabulary.explain("", CalibrationMode::Numeric).render(false), " -> 0 (no digit)");
+    }
+
+    #[test]
+    fn test_digit_vocabulary() {
+        let vocabulary = DigitVocabulary::german().with_zero("null");
+        assert_eq!(extract_calibration_value("nulleinsfünf", CalibrationMode::NumericAndWords, &vocabulary).value(), 5);
+        assert_eq!(extract_calibration_value("dreinsieben", CalibrationMode::NumericAndWords, &vocabulary).value(), 37);
+        assert_eq!(extract_calibration_value("x0", CalibrationMode::NumericAndWords, &vocabulary).value(), 0);
+
+        let vocabulary = DigitVocabulary::french().case_insensitive();
+        assert_eq!(extract_calibration_value("HuitDeuxUN", CalibrationMode::NumericAndWords, &vocabulary).value(), 81);
+        assert_eq!(extract_calibration_value("sixtwo", CalibrationMode::NumericAndWords, &vocabulary).value(), 66);
+
+        let vocabulary = DigitVocabulary::german().case_insensitive();
+        assert_eq!(extract_calibration_value("FÜNFundZwei", CalibrationMode::NumericAndWords, &vocabulary).value(), 52);
+        assert_eq!(extract_calibration_value("fÜnfNEUN", CalibrationMode::NumericAndWords, &vocabulary).value(), 59);
+        assert_eq!(extract_calibration_value("FUENF", CalibrationMode::NumericAndWords, &vocabulary).value(), 0);
+
+        let vocabulary = DigitVocabulary::numerals().with_name("leet", 7).with_name("", 1);
+        assert_eq!(extract_calibration_value("1leetone", CalibrationMode::NumericAndWords, &vocabulary).value(), 17);
+        assert_eq!(vocabulary.digits().len(), 10);
+    }
+
+    #[test]
+    fn test_digit_vocabulary_parse() {
+        let vocabulary = DigitVocabulary::parse("# value names
+case-insensitive
+
+0 0 zero
+1 1 one uno
+").unwrap();
+        assert_eq!(vocabulary, DigitVocabulary::empty()
+            .with_zero("zero")
+            .with_name("1", 1)
+            .with_name("one", 1)
+            .with_name("uno", 1)
+            .case_insensitive());
+        assert_eq!(extract_calibration_value("ZEROuno", CalibrationMode::NumericAndWords, &vocabulary).value(), 1);
+
+        let vocabulary = DigitVocabulary::parse("1 one\n10 ten");
+        assert_eq!(vocabulary.map_err(|error| (error.line, error.column, error.text)), Err((2, 1, String::from("10"))));
+
+        let vocabulary = DigitVocabulary::parse("1");
+        assert_eq!(vocabulary.map_err(|error| error.reason), Err(String::from("Digit must have at least one name!")));
+    }
+
+    #[test]
+    fn test_digit_scanner_prefers_longer_names() {
+        let vocabulary = DigitVocabulary::empty()
+            .with_name("a", 1)
+            .with_name("ab", 2)
+            .with_name("b", 3);
+        let scanner = DigitScanner::new(&vocabulary);
+        assert_eq!(scanner.calibrate(b"xabx").numeric_and_words.value(), 23);
+        assert_eq!(scanner.calibrate(b"bab").numeric_and_words.value(), 33);
+        assert_eq!(scanner.calibrate(b"ba").numeric_and_words.value(), 31);
+    }
+
+    #[test]
+    fn test_calibration_values() {
+        let document = "two1nine\r\neightwothree\n\nabcone2threexyz\nxtwone3four";
+        let values = default_vocabulary()
+            .scanner()
+            .calibrations(io::BufReader::with_capacity(3, document.as_bytes()))
+            .map(|calibrated| calibrated.map(|calibrated| calibrated.calibration.numeric_and_words.value()))
+            .collect::<io::Result<Vec<u32>>>()
+            .unwrap();
+        assert_eq!(values, vec![29, 83, 0, 13, 24]);
+
+        let values = default_vocabulary()
+            .scanner()
+            .calibrations("7\r\n".as_bytes())
+            .collect::<io::Result<Vec<CalibratedLine>>>()
+            .unwrap();
+        assert_eq!(values, vec![CalibratedLine {
+            line: b"7".to_vec(),
+            calibration: Calibration {
+                numeric: CalibrationValue::SingleDigit(7),
+                numeric_and_words: CalibrationValue::SingleDigit(7)
+            }
+        }]);
+    }
+}
\ No newline at end of file
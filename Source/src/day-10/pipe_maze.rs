@@ -1,3 +1,6 @@
+use crate::parse_error::ParseError;
+use crate::solution::Solution;
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Coords {
     x: usize,
@@ -67,27 +70,31 @@ pub struct Maze {
 }
 
 impl Maze {
-    pub fn new(s: &str) -> Self {
+    pub fn new(s: &str) -> Result<Self, ParseError> {
         let mut grid = Vec::<Vec<Tile>>::new();
         let mut starting_position = Option::<Coords>::None;
 
         for (y, line) in s.lines().enumerate() {
             let mut row = Vec::<Tile>::new();
 
-            for (x, c) in line.chars().enumerate() {
-                if let Some(tile) = Tile::new(c) {
-                    if let Tile::StartingPosition = &tile {
-                        starting_position = Some(Coords { x, y });
-                    }
+            for (x, (index, c)) in line.char_indices().enumerate() {
+                let tile = match Tile::new(c) {
+                    Some(tile) => tile,
+                    None => return Err(ParseError::new(
+                        Day10::DAY, line, &line[index..index + c.len_utf8()], "Tile must be one of '|-LJ7F.S'!").on_line(y))
+                };
 
-                    row.push(tile);
+                if let Tile::StartingPosition = &tile {
+                    starting_position = Some(Coords { x, y });
                 }
+
+                row.push(tile);
             }
 
             grid.push(row);
         }
 
-        Self { tiles: grid, starting_position }
+        Ok(Self { tiles: grid, starting_position })
     }
 
     pub fn tiles(&self) -> &Vec<Vec<Tile>> {
@@ -102,7 +109,7 @@ impl Maze {
 
     fn starting_position(&self) -> Option<&Coords> {
         match &self.starting_position {
-            Some(starting_position) => Some(&starting_position),
+            Some(starting_position) => Some(starting_position),
             None => None
         }
     }
@@ -111,9 +118,9 @@ impl Maze {
         let starting_position = self.starting_position()?;
         let mut loop_tiles = Vec::<Coords>::new();
 
-        if self.find_loop_right(&starting_position, &mut loop_tiles)
-            || self.find_loop_up(&starting_position, &mut loop_tiles)
-            || self.find_loop_left(&starting_position, &mut loop_tiles) {
+        if self.find_loop_right(starting_position, &mut loop_tiles)
+            || self.find_loop_up(starting_position, &mut loop_tiles)
+            || self.find_loop_left(starting_position, &mut loop_tiles) {
             Some(loop_tiles)
         } else {
             None
@@ -128,18 +135,18 @@ impl Maze {
                     loop_tiles.push(from.clone());
                     true
                 }
-                Some(Tile::HorizontalPipe) => if self.find_loop_right(right, loop_tiles) {
+                Some(Tile::HorizontalPipe) if self.find_loop_right(right, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
-                Some(Tile::NorthWestBend) => if self.find_loop_up(right, loop_tiles) {
+                }
+                Some(Tile::NorthWestBend) if self.find_loop_up(right, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
-                Some(Tile::SouthWestBend) => if self.find_loop_down(right, loop_tiles) {
+                }
+                Some(Tile::SouthWestBend) if self.find_loop_down(right, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
+                }
                 _ => false
             }
         }
@@ -153,18 +160,18 @@ impl Maze {
                     loop_tiles.push(from.clone());
                     true
                 }
-                Some(Tile::HorizontalPipe) => if self.find_loop_left(left, loop_tiles) {
+                Some(Tile::HorizontalPipe) if self.find_loop_left(left, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
-                Some(Tile::NorthEastBend) => if self.find_loop_up(left, loop_tiles) {
+                }
+                Some(Tile::NorthEastBend) if self.find_loop_up(left, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
-                Some(Tile::SouthEastBend) => if self.find_loop_down(left, loop_tiles) {
+                }
+                Some(Tile::SouthEastBend) if self.find_loop_down(left, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
+                }
                 _ => false
             }
         }
@@ -178,18 +185,18 @@ impl Maze {
                     loop_tiles.push(from.clone());
                     true
                 }
-                Some(Tile::VerticalPipe) => if self.find_loop_up(up, loop_tiles) {
+                Some(Tile::VerticalPipe) if self.find_loop_up(up, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
-                Some(Tile::SouthEastBend) => if self.find_loop_right(up, loop_tiles) {
+                }
+                Some(Tile::SouthEastBend) if self.find_loop_right(up, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
-                Some(Tile::SouthWestBend) => if self.find_loop_left(up, loop_tiles) {
+                }
+                Some(Tile::SouthWestBend) if self.find_loop_left(up, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
+                }
                 _ => false
             }
         }
@@ -203,18 +210,18 @@ impl Maze {
                     loop_tiles.push(from.clone());
                     true
                 }
-                Some(Tile::VerticalPipe) => if self.find_loop_down(down, loop_tiles) {
+                Some(Tile::VerticalPipe) if self.find_loop_down(down, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
-                Some(Tile::NorthEastBend) => if self.find_loop_right(down, loop_tiles) {
+                }
+                Some(Tile::NorthEastBend) if self.find_loop_right(down, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
-                Some(Tile::NorthWestBend) => if self.find_loop_left(down, loop_tiles) {
+                }
+                Some(Tile::NorthWestBend) if self.find_loop_left(down, loop_tiles) => {
                     loop_tiles.push(from.clone());
                     true
-                } else { false }
+                }
                 _ => false
             }
         }
@@ -223,25 +230,44 @@ impl Maze {
     pub fn loop_length(&self) -> Option<u32> {
         let starting_position = self.starting_position()?;
 
-        if let Some(distance) = self.left_distance(&starting_position) {
+        if let Some(distance) = self.left_distance(starting_position) {
             return Some(distance);
         }
 
-        if let Some(distance) = self.right_distance(&starting_position) {
+        if let Some(distance) = self.right_distance(starting_position) {
             return Some(distance);
         }
 
-        if let Some(distance) = self.up_distance(&starting_position) {
+        if let Some(distance) = self.up_distance(starting_position) {
             return Some(distance);
         }
 
-        if let Some(distance) = self.down_distance(&starting_position) {
+        if let Some(distance) = self.down_distance(starting_position) {
             return Some(distance);
         }
 
         None
     }
 
+    pub fn farthest_distance(&self) -> Option<u32> {
+        let loop_length = self.loop_length()?;
+        Some(if loop_length % 2 == 0 { loop_length / 2 } else { loop_length / 2 + 1 })
+    }
+
+    pub fn enclosed_tile_count(&self) -> Option<u64> {
+        let tile_loop = self.find_loop()?;
+        let boundary_count = tile_loop.len() as i64;
+        let double_area = tile_loop
+            .iter()
+            .zip(tile_loop.iter().cycle().skip(1))
+            .map(|(current, next)| current.x as i64 * next.y as i64 - next.x as i64 * current.y as i64)
+            .sum::<i64>()
+            .abs();
+
+        // Pick's theorem: A = i + b/2 - 1
+        Some(((double_area - boundary_count) / 2 + 1) as u64)
+    }
+
     fn right_distance(&self, from: &Coords) -> Option<u32> {
         match &from.right() {
             None => None,
@@ -324,6 +350,29 @@ impl Tile {
     }
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Pipe Maze";
+
+    type Input<'a> = Maze;
+    type Part1 = u32;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Maze::new(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        input.farthest_distance()
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        input.enclosed_tile_count()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,26 +383,48 @@ mod tests {
 7S-7|
 L|7||
 -L-J|
-L|-JF");
+L|-JF").unwrap();
         assert_eq!(maze.loop_length(), Some(8));
 
         let maze = Maze::new("7-F7-
 .FJ|7
 SJLL7
 |F--J
-LJ.LJ");
+LJ.LJ").unwrap();
         assert_eq!(maze.loop_length(), Some(16));
     }
 
+    #[test]
+    fn test_maze_new_invalid() {
+        let maze = Maze::new("7-F7-
+.FJ|7
+SJLX7");
+        assert_eq!(maze.map_err(|error| (error.line, error.column, error.text)), Err((3, 4, String::from("X"))));
+    }
+
     #[test]
     fn test_find_loop() {
         let maze = Maze::new(".......
 7S-7--.
 .|.L7.-
--L--J");
+-L--J").unwrap();
         let tile_loop = maze.find_loop().unwrap();
         for tile in tile_loop {
             println!("{tile:?}");
         }
     }
+
+    #[test]
+    fn test_enclosed_tile_count() {
+        let maze = Maze::new("...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........").unwrap();
+        assert_eq!(maze.enclosed_tile_count(), Some(4));
+    }
 }
\ No newline at end of file
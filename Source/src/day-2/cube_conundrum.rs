@@ -1,69 +1,137 @@
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::parse_error::ParseError;
+use crate::solution::Solution;
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct Cubes<'a> {
-    kind: &'a str,
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Cubes {
+    kind: String,
     count: u32
 }
 
-impl Cubes<'_> {
-    fn from_str(str: &str) -> Result<Cubes, String> {
-        let str = str.trim();
-        let space_index = str.find(' ');
+impl Cubes {
+    pub fn new(kind: &str, count: u32) -> Self {
+        Cubes { kind: String::from(kind), count }
+    }
+
+    fn from_str(str: &str) -> Result<Cubes, ParseError> {
+        let trimmed = str.trim();
+        let space_index = trimmed.find(' ');
         match space_index {
-            None => Err(String::from("Invalid string format! Cubes string must be '<count> <kind>'.")),
+            None => Err(ParseError::new(Day2::DAY, str, trimmed, "Cubes string must be '<count> <kind>'!")),
             Some(space_index) => {
-                let count_str = &str[..space_index];
-                let kind = &str[space_index+1..];
+                let count_str = &trimmed[..space_index];
+                let kind = &trimmed[space_index+1..];
                 match count_str.parse::<u32>() {
-                    Ok(count) => Ok(Cubes { kind, count }),
-                    Err(parse_error) => Err(String::from(format!("Unable to parse cubes count {count_str}!\n{parse_error}")))
+                    Ok(count) => Ok(Cubes::new(kind, count)),
+                    Err(parse_error) => Err(ParseError::new(Day2::DAY, str, count_str, format!("Unable to parse cubes count! {parse_error}")))
                 }
             }
         }
     }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct CubesSet<'a> {
-    cubes: Vec<Cubes<'a>>
+impl Display for Cubes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.count, self.kind)
+    }
+}
+
+/// Cubes shown at once, serialized as a plain list of cubes.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CubesSet {
+    cubes: Vec<Cubes>
 }
 
-impl CubesSet<'_> {
-    fn from_str(str: &str) -> CubesSet {
+impl CubesSet {
+    pub fn new(cubes: Vec<Cubes>) -> Self {
+        CubesSet { cubes }
+    }
+
+    pub fn cubes(&self) -> &[Cubes] {
+        &self.cubes
+    }
+
+    /// Number of cubes of `kind` shown, 0 if there are none.
+    pub fn count(&self, kind: &str) -> u32 {
+        self.cubes
+            .iter()
+            .filter(|cubes| cubes.kind == kind)
+            .map(|cubes| cubes.count)
+            .sum()
+    }
+
+    pub fn power(&self) -> u32 {
+        self.cubes.iter().fold(1_u32, |prod, cube| prod * cube.count)
+    }
+}
+
+impl FromStr for CubesSet {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
         let cubes = str
             .trim()
             .split(',')
-            .map(Cubes::from_str)
-            .filter_map(|result| match result {
-                Ok(cubes) => Some(cubes),
-                Err(_) => None
-            })
-            .collect::<Vec<Cubes>>();
-        CubesSet { cubes }
+            .map(|cubes| Cubes::from_str(cubes).map_err(|error| error.within(str, cubes)))
+            .collect::<Result<Vec<Cubes>, ParseError>>()?;
+        Ok(CubesSet { cubes })
     }
+}
 
-    pub fn power(self: &Self) -> u32 {
-        self.cubes.iter().fold(1_u32, |prod, cube| prod * cube.count)
+impl Display for CubesSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, cubes) in self.cubes.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{cubes}")?;
+        }
+
+        Ok(())
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct Game<'a> {
+/// A game, owning its data so that it can be stored, sent to other threads or saved as JSON.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Game {
     pub id: u32,
-    sets: Vec<CubesSet<'a>>
+    sets: Vec<CubesSet>
 }
 
-impl<'a, 'b> Game<'a> where 'a: 'b {
-    pub fn from_line(line: &str) -> Result<Game, String> {
+impl Game {
+    pub fn new(id: u32, sets: Vec<CubesSet>) -> Self {
+        Game { id, sets }
+    }
+
+    pub fn extract(str: &str) -> Result<Vec<Game>, ParseError> {
+        str
+            .lines()
+            .enumerate()
+            .map(|(index, line)| Game::from_line(line).map_err(|error| error.on_line(index)))
+            .collect::<Result<Vec<Game>, ParseError>>()
+    }
+
+    pub fn from_line(line: &str) -> Result<Game, ParseError> {
         if !line.starts_with("Game ") {
-            return Err(String::from("Game line must start with 'Game <id>:'!"));
+            return Err(ParseError::new(Day2::DAY, line, line, "Game line must start with 'Game <id>:'!"));
         }
 
-        let colon_index = line.chars().position(|char| char == ':');
+        let colon_index = line.find(':');
         match colon_index {
-            None => Err(String::from("Game line must start with 'Game <id>:'!")),
+            None => Err(ParseError::new(Day2::DAY, line, line, "Game line must start with 'Game <id>:'!")),
             Some(colon_index) => {
                 let id_str = &line[5..colon_index];
                 let id = id_str.parse::<u32>();
@@ -71,27 +139,161 @@ impl<'a, 'b> Game<'a> where 'a: 'b {
                     Ok(id) => {
                         let sets = line[colon_index + 1..]
                             .split(';')
-                            .map(CubesSet::from_str)
-                            .collect::<Vec<CubesSet>>();
+                            .map(|set| CubesSet::from_str(set).map_err(|error| error.within(line, set)))
+                            .collect::<Result<Vec<CubesSet>, ParseError>>()?;
                         Ok(Game { id, sets })
                     },
-                    Err(error) => Err(format!("Unable to parse game id {id_str}!\n{error}"))
+                    Err(error) => Err(ParseError::new(Day2::DAY, line, id_str, format!("Unable to parse game id! {error}")))
                 }
             }
         }
     }
 
-    pub fn is_allowed_by_constraint(self: &Self, constraints: &Vec<MaxBallsConstraint>) -> bool {
-        !constraints.iter().any(|constraint| self.sets
+    pub fn sets(&self) -> &[CubesSet] {
+        &self.sets
+    }
+
+    pub fn to_json(games: &[Game]) -> String {
+        serde_json::to_string_pretty(games).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Vec<Game>> {
+        serde_json::from_str(json)
+    }
+
+    /// Estimates the bag the cubes of all `games` were drawn from, see [BagModel].
+    pub fn estimate_bag(games: &[Game], model: &BagModel) -> BagEstimate {
+        let draws = Draws::new(games, model.max_count);
+        let minimums = (0..draws.kinds.len())
+            .map(|kind| draws.handfuls.iter().map(|handful| handful[kind]).max().unwrap_or(0))
+            .collect::<Vec<u32>>();
+        let range = |kind: usize| minimums[kind]..=model.max_count.max(minimums[kind]);
+
+        // Coordinate ascent, every step strictly increases the likelihood so it always ends.
+        let mut counts = minimums.clone();
+        let mut log_likelihood = draws.log_likelihood(&counts, 0);
+        loop {
+            let mut improved = false;
+            for kind in 0..counts.len() {
+                for count in range(kind) {
+                    let mut candidate = counts.clone();
+                    candidate[kind] = count;
+                    let candidate_log_likelihood = draws.log_likelihood(&candidate, 0);
+                    if candidate_log_likelihood > log_likelihood + f64::EPSILON {
+                        counts = candidate;
+                        log_likelihood = candidate_log_likelihood;
+                        improved = true;
+                    }
+                }
+            }
+
+            if !improved {
+                break;
+            }
+        }
+
+        let threshold = likelihood_ratio_threshold(model.confidence);
+        let kinds = draws.kinds
             .iter()
-            .flat_map(|set| &set.cubes)
-            .any(|cubes| cubes.kind == constraint.kind && cubes.count > constraint.max_count))
+            .enumerate()
+            .map(|(kind, name)| {
+                let plausible = range(kind)
+                    .filter(|&count| {
+                        let mut candidate = counts.clone();
+                        candidate[kind] = count;
+                        draws.log_likelihood(&candidate, 0) >= log_likelihood - threshold
+                    })
+                    .collect::<Vec<u32>>();
+
+                KindEstimate {
+                    kind: name.clone(),
+                    count: counts[kind],
+                    minimum: minimums[kind],
+                    lower: plausible.first().copied().unwrap_or(counts[kind]),
+                    upper: plausible.last().copied().unwrap_or(counts[kind])
+                }
+            })
+            .collect::<Vec<KindEstimate>>();
+
+        BagEstimate { kinds, log_likelihood }
+    }
+
+    /// Ranks bags described by constraints, like "12 red, 13 green, 14 blue", from the most to the least likely to
+    /// have produced `games`.
+    ///
+    /// Bags that can't have produced some game have a likelihood of 0.
+    pub fn rank_constraints<'c>(games: &[Game], candidates: &'c [Vec<MaxBallsConstraint>]) -> Vec<RankedConstraints<'c>> {
+        let max_count = candidates
+            .iter()
+            .map(|constraints| constraints.iter().map(|constraint| constraint.max_count).sum::<u32>())
+            .max()
+            .unwrap_or(0);
+        let draws = Draws::new(games, max_count);
+
+        let mut ranked = candidates
+            .iter()
+            .map(|constraints| {
+                let counts = draws.kinds
+                    .iter()
+                    .map(|kind| constraints
+                        .iter()
+                        .filter(|constraint| &constraint.kind == kind)
+                        .map(|constraint| constraint.max_count)
+                        .sum::<u32>())
+                    .collect::<Vec<u32>>();
+                let others = constraints
+                    .iter()
+                    .filter(|constraint| !draws.kinds.contains(&constraint.kind))
+                    .map(|constraint| constraint.max_count)
+                    .sum::<u32>();
+
+                RankedConstraints {
+                    constraints,
+                    log_likelihood: draws.log_likelihood(&counts, others),
+                    relative_likelihood: 0.0
+                }
+            })
+            .collect::<Vec<RankedConstraints>>();
+        ranked.sort_by(|left, right| right.log_likelihood.total_cmp(&left.log_likelihood));
+
+        let best = ranked.first().map(|ranked| ranked.log_likelihood).unwrap_or(0.0);
+        for ranked in &mut ranked {
+            if ranked.log_likelihood.is_finite() {
+                ranked.relative_likelihood = (ranked.log_likelihood - best).exp();
+            }
+        }
+
+        ranked
+    }
+
+    pub fn is_allowed_by_constraint(&self, constraints: &[MaxBallsConstraint]) -> bool {
+        self.violations(constraints).is_empty()
+    }
+
+    /// Constraints exceeded by any set of the game, in the order of `constraints`.
+    pub fn violations<'c>(&self, constraints: &'c [MaxBallsConstraint]) -> Vec<Violation<'c>> {
+        constraints
+            .iter()
+            .filter_map(|constraint| {
+                let count = self.sets
+                    .iter()
+                    .flat_map(|set| &set.cubes)
+                    .filter(|cubes| cubes.kind == constraint.kind)
+                    .map(|cubes| cubes.count)
+                    .max()?;
+                (count > constraint.max_count).then_some(Violation {
+                    kind: &constraint.kind,
+                    max_count: constraint.max_count,
+                    count
+                })
+            })
+            .collect::<Vec<Violation>>()
     }
 
-    pub fn get_minimum_required_set(self: &Self) -> CubesSet<'b> {
+    pub fn get_minimum_required_set(&self) -> CubesSet {
         let mut min_required = HashMap::<&str, u32>::new();
         for cubes in self.sets.iter().flat_map(|set| &set.cubes) {
-            let entry = min_required.entry(cubes.kind);
+            let entry = min_required.entry(&cubes.kind);
             match entry {
                 Entry::Occupied(mut occupied) => {
                     if *occupied.get() < cubes.count {
@@ -106,71 +308,309 @@ impl<'a, 'b> Game<'a> where 'a: 'b {
 
         let cubes = min_required
             .iter()
-            .map(|(&kind, &count)| {
-                let kind = kind.clone();
-                let count = count.clone();
-                Cubes { kind, count }
-            })
+            .map(|(&kind, &count)| Cubes::new(kind, count))
             .collect::<Vec<Cubes>>();
         CubesSet { cubes }
     }
 }
 
-pub struct MaxBallsConstraint<'a> {
-    kind: &'a str,
+/// The constraints of part 1, the bag holds only 12 red cubes, 13 green cubes and 14 blue cubes.
+pub const PUZZLE_CONSTRAINTS: &str = "12 red, 13 green, 14 blue";
+
+/// Writes the game as a puzzle line, `Game 1: 3 blue, 4 red; 1 red`.
+///
+/// The line parses back into the same game, unless a set is empty or a kind of cubes contains `,` or `;`.
+impl Display for Game {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Game {}:", self.id)?;
+        for (index, set) in self.sets.iter().enumerate() {
+            let separator = if index > 0 { ";" } else { "" };
+            write!(f, "{separator} {set}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MaxBallsConstraint {
+    kind: String,
     max_count: u32
 }
 
-impl<'a> MaxBallsConstraint<'a> {
-    pub fn new(kind: &'a str, max_count: u32) -> MaxBallsConstraint {
+impl MaxBallsConstraint {
+    pub fn new(kind: &str, max_count: u32) -> MaxBallsConstraint {
         MaxBallsConstraint {
-            kind,
+            kind: String::from(kind),
             max_count
         }
     }
+
+    /// Parses constraints written like a set of cubes, e.g. "12 red, 13 green, 14 blue".
+    pub fn parse_all(str: &str) -> Result<Vec<MaxBallsConstraint>, ParseError> {
+        Ok(CubesSet::from_str(str)?
+            .cubes
+            .into_iter()
+            .map(|cubes| MaxBallsConstraint { kind: cubes.kind, max_count: cubes.count })
+            .collect::<Vec<MaxBallsConstraint>>())
+    }
+}
+
+/// A constraint that a game exceeds, `count` is the largest number of cubes of that kind shown at once.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Violation<'a> {
+    pub kind: &'a str,
+    pub max_count: u32,
+    pub count: u32
+}
+
+/// Sum of the ids of the games that are possible under `constraints`.
+pub fn possible_games_id_sum(games: &[Game], constraints: &[MaxBallsConstraint]) -> u32 {
+    games
+        .iter()
+        .filter(|game| game.violations(constraints).is_empty())
+        .map(|game| game.id)
+        .sum()
+}
+
+/// How bags are estimated.
+///
+/// Each set is a handful of cubes drawn from the bag without replacement, and the cubes are put back before the next
+/// set. The likelihood of a handful is then multivariate hypergeometric.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BagModel {
+    /// Largest number of cubes of a kind considered. Some draws are more likely the larger the bag gets, their
+    /// estimates and intervals stop at this count.
+    pub max_count: u32,
+    /// Confidence of the intervals, between 0 and 1.
+    pub confidence: f64
+}
+
+impl Default for BagModel {
+    fn default() -> Self {
+        BagModel { max_count: 100, confidence: 0.95 }
+    }
+}
+
+/// Most likely bag, with one estimate per kind of cubes seen, ordered by kind.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BagEstimate {
+    pub kinds: Vec<KindEstimate>,
+    pub log_likelihood: f64
+}
+
+/// Estimated number of cubes of a kind.
+///
+/// `lower..=upper` is a likelihood ratio interval, computed with the other kinds at their estimates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KindEstimate {
+    pub kind: String,
+    pub count: u32,
+    /// Most cubes of the kind shown at once, the bag holds at least as many.
+    pub minimum: u32,
+    pub lower: u32,
+    pub upper: u32
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedConstraints<'c> {
+    pub constraints: &'c [MaxBallsConstraint],
+    pub log_likelihood: f64,
+    /// Likelihood compared to the most likely candidate, 1 for the best one and 0 for impossible ones.
+    pub relative_likelihood: f64
+}
+
+/// Every set of a list of games as counts per kind.
+struct Draws {
+    kinds: Vec<String>,
+    handfuls: Vec<Vec<u32>>,
+    /// `ln(n!)` for every `n` up to the largest bag that's evaluated.
+    ln_factorials: Vec<f64>
+}
+
+impl Draws {
+    fn new(games: &[Game], max_count: u32) -> Self {
+        let kinds = games
+            .iter()
+            .flat_map(|game| &game.sets)
+            .flat_map(|set| &set.cubes)
+            .map(|cubes| cubes.kind.clone())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect::<Vec<String>>();
+        let handfuls = games
+            .iter()
+            .flat_map(|game| &game.sets)
+            .map(|set| kinds
+                .iter()
+                .map(|kind| set.cubes.iter().filter(|cubes| &cubes.kind == kind).map(|cubes| cubes.count).sum::<u32>())
+                .collect::<Vec<u32>>())
+            .collect::<Vec<Vec<u32>>>();
+
+        let largest_handful = handfuls.iter().map(|handful| handful.iter().sum::<u32>()).max().unwrap_or(0);
+        let largest_bag = (max_count.max(largest_handful) as usize) * kinds.len().max(1);
+        let mut ln_factorials = vec![0.0; largest_bag + 1];
+        for n in 1..ln_factorials.len() {
+            ln_factorials[n] = ln_factorials[n - 1] + (n as f64).ln();
+        }
+
+        Draws { kinds, handfuls, ln_factorials }
+    }
+
+    /// Log-likelihood of the draws from a bag with `counts` cubes of each kind and `others` cubes of unseen kinds.
+    fn log_likelihood(&self, counts: &[u32], others: u32) -> f64 {
+        let total = counts.iter().sum::<u32>() + others;
+
+        self.handfuls
+            .iter()
+            .map(|handful| {
+                let drawn = handful.iter().sum::<u32>();
+                let favorable = counts
+                    .iter()
+                    .zip(handful)
+                    .map(|(&count, &shown)| self.ln_binomial(count, shown))
+                    .sum::<f64>();
+                if favorable == f64::NEG_INFINITY || drawn > total {
+                    return f64::NEG_INFINITY;
+                }
+                favorable - self.ln_binomial(total, drawn)
+            })
+            .sum()
+    }
+
+    fn ln_binomial(&self, n: u32, k: u32) -> f64 {
+        if k > n {
+            return f64::NEG_INFINITY;
+        }
+
+        let ln_factorial = |n: u32| match self.ln_factorials.get(n as usize) {
+            Some(&ln_factorial) => ln_factorial,
+            None => (1..=n).map(|i| (i as f64).ln()).sum()
+        };
+        ln_factorial(n) - ln_factorial(k) - ln_factorial(n - k)
+    }
+}
+
+/// Drop of the log-likelihood that still lies within an interval of `confidence`, half the chi-squared quantile with
+/// one degree of freedom.
+fn likelihood_ratio_threshold(confidence: f64) -> f64 {
+    let confidence = confidence.clamp(0.0, 1.0 - 1e-12);
+    // Bisection of the two-sided normal quantile, P(|Z| <= z) = erf(z / sqrt(2)).
+    let (mut low, mut high) = (0.0_f64, 10.0_f64);
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if erf(middle / std::f64::consts::SQRT_2) < confidence {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    low * low / 2.0
+}
+
+/// Error function, Abramowitz and Stegun 7.1.26, precise to 1.5e-7.
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let polynomial = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - polynomial * (-x * x).exp();
+
+    if x < 0.0 { -erf } else { erf }
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Cube Conundrum";
+
+    type Input<'a> = Vec<Game>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Game::extract(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        let constraints = MaxBallsConstraint::parse_all(PUZZLE_CONSTRAINTS).ok()?;
+        Some(possible_games_id_sum(input, &constraints))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        Some(input
+            .iter()
+            .map(|game| game.get_minimum_required_set().power())
+            .sum())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::generator::Rng;
     use super::*;
 
+    const KINDS: [&str; 5] = ["red", "green", "blue", "light blue", "x"];
+
+    fn random_game(rng: &mut Rng) -> Game {
+        let sets = (0..rng.between(1, 8))
+            .map(|_| {
+                let cubes = (0..rng.between(1, 6))
+                    .map(|_| {
+                        let kind = rng.choose(&KINDS);
+                        Cubes::new(kind, rng.next_u64() as u32 >> rng.between(0, 31))
+                    })
+                    .collect::<Vec<Cubes>>();
+                CubesSet::new(cubes)
+            })
+            .collect::<Vec<CubesSet>>();
+
+        Game::new(rng.next_u64() as u32, sets)
+    }
+
     #[test]
     fn test_cubes_from_substr() {
         let cubes = Cubes::from_str("3 blue");
-        assert_eq!(cubes, Ok(Cubes {
-            kind: "blue",
-            count: 3
-        }));
+        assert_eq!(cubes, Ok(Cubes::new("blue", 3)));
 
         let cubes = Cubes::from_str("2 green");
-        assert_eq!(cubes, Ok(Cubes {
-            kind: "green",
-            count: 2
-        }));
+        assert_eq!(cubes, Ok(Cubes::new("green", 2)));
 
         let cubes = Cubes::from_str("\t5109 yellow   ");
-        assert_eq!(cubes, Ok(Cubes {
-            kind: "yellow",
-            count: 5109
-        }));
+        assert_eq!(cubes, Ok(Cubes::new("yellow", 5109)));
 
         let cubes = Cubes::from_str("3blue");
-        assert_eq!(cubes, Err(String::from("Invalid string format! Cubes string must be '<count> <kind>'.")));
+        assert_eq!(cubes, Err(ParseError {
+            day: 2,
+            line: 1,
+            column: 1,
+            text: String::from("3blue"),
+            reason: String::from("Cubes string must be '<count> <kind>'!")
+        }));
 
-        let cubes = Cubes::from_str("-1 blue");
-        assert_eq!(cubes, Err(String::from("Unable to parse cubes count -1!\ninvalid digit found in string")));
+        let cubes = Cubes::from_str(" -1 blue");
+        assert_eq!(cubes, Err(ParseError {
+            day: 2,
+            line: 1,
+            column: 2,
+            text: String::from("-1"),
+            reason: String::from("Unable to parse cubes count! invalid digit found in string")
+        }));
     }
 
     #[test]
     fn test_cubes_set_from_substr() {
         let cubes_set = CubesSet::from_str("8 green, 6 blue, 20 red");
-        assert_eq!(cubes_set, CubesSet {
+        assert_eq!(cubes_set, Ok(CubesSet {
             cubes: vec![
-                Cubes { kind: "green", count: 8 },
-                Cubes { kind: "blue", count: 6 },
-                Cubes { kind: "red", count: 20 }
+                Cubes::new("green", 8),
+                Cubes::new("blue", 6),
+                Cubes::new("red", 20)
             ]
-        });
+        }));
+
+        let cubes_set = CubesSet::from_str("8 green, 6blue");
+        assert_eq!(cubes_set.map_err(|error| error.column), Err(10));
     }
 
     #[test]
@@ -181,58 +621,194 @@ mod tests {
             sets: vec![
                 CubesSet {
                     cubes: vec![
-                        Cubes {
-                            kind: "blue",
-                            count: 3
-                        },
-                        Cubes {
-                            kind: "red",
-                            count: 4
-                        }
+                        Cubes::new("blue", 3),
+                        Cubes::new("red", 4)
                     ]
                 },
                 CubesSet {
                     cubes: vec![
-                        Cubes {
-                            kind: "red",
-                            count: 1
-                        },
-                        Cubes {
-                            kind: "green",
-                            count: 2
-                        },
-                        Cubes {
-                            kind: "blue",
-                            count: 6
-                        }
+                        Cubes::new("red", 1),
+                        Cubes::new("green", 2),
+                        Cubes::new("blue", 6)
                     ]
                 },
                 CubesSet {
                     cubes: vec![
-                        Cubes {
-                            kind: "green",
-                            count: 2
-                        }
+                        Cubes::new("green", 2)
                     ]
                 }
             ]
         }));
+
+        let game = Game::from_line("Game x: 3 blue");
+        assert_eq!(game.map_err(|error| (error.column, error.text)), Err((6, String::from("x"))));
+
+        let game = Game::from_line("Game 1: 3 blue; 4 red, 1 purple");
+        assert!(game.is_ok());
+
+        let game = Game::from_line("Game 1: 3 blue; 4 red, one green");
+        assert_eq!(game.map_err(|error| (error.column, error.text)), Err((24, String::from("one"))));
     }
 
     #[test]
     fn test_is_allowed_by_constraint() {
         let game = Game::from_line("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green")
             .expect("Game::from_line() should've created a valid Game!");
-        assert!(game.is_allowed_by_constraint(&vec![
+        assert!(game.is_allowed_by_constraint(&[
             MaxBallsConstraint::new("red", 12),
             MaxBallsConstraint::new("green", 13),
             MaxBallsConstraint::new("blue", 14)]));
-        assert!(!game.is_allowed_by_constraint(&vec![
+        assert!(!game.is_allowed_by_constraint(&[
             MaxBallsConstraint::new("red", 2),
             MaxBallsConstraint::new("green", 13),
             MaxBallsConstraint::new("blue", 14)]));
     }
 
+    #[test]
+    fn test_display() {
+        let line = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        assert_eq!(Game::from_line(line).unwrap().to_string(), line);
+        assert_eq!(Game::from_line("Game 12:3 blue ,4 red;  1 red").unwrap().to_string(), "Game 12: 3 blue, 4 red; 1 red");
+        assert_eq!(CubesSet::from_str("8 green, 6 blue").unwrap().to_string(), "8 green, 6 blue");
+        assert_eq!(Cubes::new("light blue", 0).to_string(), "0 light blue");
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng::new(2023);
+        for _ in 0..500 {
+            let game = random_game(&mut rng);
+            let line = game.to_string();
+            assert_eq!(Game::from_line(&line), Ok(game), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_game_json() {
+        let games = Game::extract("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue").unwrap();
+        let json = Game::to_json(&games);
+        assert_eq!(Game::from_json(&json).unwrap(), games);
+
+        let games = Game::from_json(r#"[{"id": 7, "sets": [[{"kind": "red", "count": 3}], []]}]"#).unwrap();
+        assert_eq!(games, vec![Game::new(7, vec![CubesSet::new(vec![Cubes::new("red", 3)]), CubesSet::new(vec![])])]);
+        assert!(Game::from_json(r#"[{"id": 7, "sets": [[{"kind": "red"}]]}]"#).is_err());
+    }
+
+    #[test]
+    fn test_games_are_owned() {
+        let games = {
+            let input = String::from("Game 1: 3 blue, 4 red");
+            Game::extract(&input).unwrap()
+        };
+        let handle = std::thread::spawn(move || games.iter().map(|game| game.get_minimum_required_set().power()).sum::<u32>());
+        assert_eq!(handle.join().unwrap(), 12);
+    }
+
+    #[test]
+    fn test_ln_binomial() {
+        let draws = Draws::new(&[], 10);
+        assert!((draws.ln_binomial(5, 2) - 10_f64.ln()).abs() < 1e-9);
+        assert!((draws.ln_binomial(60, 30) - 118264581564861424_f64.ln()).abs() < 1e-9);
+        assert_eq!(draws.ln_binomial(2, 3), f64::NEG_INFINITY);
+        assert!((likelihood_ratio_threshold(0.95) - 1.92073).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_estimate_bag() {
+        let games = Game::extract("Game 1: 1 red, 1 blue
+Game 2: 1 red, 1 blue
+Game 3: 1 red, 1 blue").unwrap();
+        let estimate = Game::estimate_bag(&games, &BagModel::default());
+        let [blue, red] = estimate.kinds.as_slice() else { panic!("{estimate:?}") };
+        assert_eq!((blue.kind.as_str(), red.kind.as_str()), ("blue", "red"));
+        assert_eq!(blue.count, red.count);
+        assert_eq!(blue.minimum, 1);
+
+        // A single kind is always drawn whatever its count, so the smallest possible bag is kept.
+        let games = Game::extract("Game 1: 3 red\nGame 2: 5 red").unwrap();
+        let estimate = Game::estimate_bag(&games, &BagModel { max_count: 20, confidence: 0.95 });
+        assert_eq!(estimate.kinds, vec![KindEstimate { kind: String::from("red"), count: 5, minimum: 5, lower: 5, upper: 20 }]);
+
+        let games = Game::extract("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red").unwrap();
+        let estimate = Game::estimate_bag(&games, &BagModel::default());
+        for kind in &estimate.kinds {
+            assert!(kind.minimum <= kind.lower && kind.lower <= kind.count && kind.count <= kind.upper, "{kind:?}");
+        }
+        assert_eq!(estimate.kinds.iter().map(|kind| kind.minimum).collect::<Vec<u32>>(), vec![6, 13, 20]);
+    }
+
+    #[test]
+    fn test_rank_constraints() {
+        let games = Game::extract("Game 1: 1 red, 1 blue; 1 blue, 1 red
+Game 2: 1 red, 1 blue").unwrap();
+        // A handful of 1 red and 1 blue has a probability of 100/190, 900/4950, 0 and 100/4950 respectively.
+        let candidates = [
+            MaxBallsConstraint::parse_all("10 red").unwrap(),
+            MaxBallsConstraint::parse_all("10 red, 90 blue").unwrap(),
+            MaxBallsConstraint::parse_all("10 red, 10 blue").unwrap(),
+            MaxBallsConstraint::parse_all("10 red, 10 blue, 80 purple").unwrap()
+        ];
+
+        let ranked = Game::rank_constraints(&games, &candidates);
+        let order = ranked
+            .iter()
+            .map(|ranked| candidates.iter().position(|candidate| candidate.as_slice() == ranked.constraints).unwrap())
+            .collect::<Vec<usize>>();
+        assert_eq!(order, vec![2, 1, 3, 0]);
+        assert_eq!(ranked[0].relative_likelihood, 1.0);
+        assert!((ranked[0].log_likelihood - 3.0 * (100.0_f64 / 190.0).ln()).abs() < 1e-9);
+        assert!((ranked[1].relative_likelihood - (900.0_f64 / 4950.0 / (100.0 / 190.0)).powi(3)).abs() < 1e-9);
+        assert!(ranked[2].relative_likelihood < ranked[1].relative_likelihood);
+        assert_eq!(ranked[3].relative_likelihood, 0.0);
+        assert_eq!(ranked[3].log_likelihood, f64::NEG_INFINITY);
+
+        // A bag with fewer cubes than a handful can't have produced it.
+        let games = Game::extract("Game 1: 3 red, 3 blue").unwrap();
+        let candidates = [
+            MaxBallsConstraint::parse_all("1 red, 1 blue").unwrap(),
+            MaxBallsConstraint::parse_all("3 red, 3 blue").unwrap()
+        ];
+        let ranked = Game::rank_constraints(&games, &candidates);
+        assert_eq!(ranked[0].constraints, candidates[1].as_slice());
+        assert_eq!(ranked[0].relative_likelihood, 1.0);
+        assert_eq!(ranked[1].relative_likelihood, 0.0);
+        assert_eq!(ranked[1].log_likelihood, f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_violations() {
+        let game = Game::from_line("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red")
+            .expect("Game::from_line() should've created a valid Game!");
+        let constraints = MaxBallsConstraint::parse_all(PUZZLE_CONSTRAINTS).unwrap();
+        assert_eq!(game.violations(&constraints), vec![Violation { kind: "red", max_count: 12, count: 20 }]);
+
+        let constraints = MaxBallsConstraint::parse_all("5 blue, 1 yellow, 7 green").unwrap();
+        assert_eq!(game.violations(&constraints), vec![
+            Violation { kind: "blue", max_count: 5, count: 6 },
+            Violation { kind: "green", max_count: 7, count: 13 }
+        ]);
+        assert!(!game.is_allowed_by_constraint(&constraints));
+
+        let constraints = MaxBallsConstraint::parse_all("12 red, 13 green\n");
+        assert_eq!(constraints.map(|constraints| constraints.len()), Ok(2));
+
+        let constraints = MaxBallsConstraint::parse_all("12 red, green");
+        assert_eq!(constraints.map_err(|error| (error.column, error.text)), Err((9, String::from("green"))));
+    }
+
+    #[test]
+    fn test_possible_games_id_sum() {
+        let games = Game::extract("Game 1: 3 blue, 4 red
+Game 2: 20 red
+Game 3: 1 green").unwrap();
+        let constraints = MaxBallsConstraint::parse_all(PUZZLE_CONSTRAINTS).unwrap();
+        assert_eq!(possible_games_id_sum(&games, &constraints), 4);
+        assert_eq!(possible_games_id_sum(&games, &[]), 6);
+    }
+
     #[test]
     fn test_get_minimum_required_set() {
         let game = Game::from_line("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green")
@@ -244,7 +820,7 @@ mod tests {
 
     #[test]
     fn test_cubes_set_power() {
-        let cubes_set = CubesSet::from_str("8 green, 6 blue, 20 red");
+        let cubes_set = CubesSet::from_str("8 green, 6 blue, 20 red").unwrap();
         assert_eq!(cubes_set.power(), 8 * 6 * 20);
     }
 }
\ No newline at end of file
@@ -1,41 +1,156 @@
 // https://adventofcode.com/2023/day/2
 
-use std::{env, fs, path};
-use cube_conundrum::*;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use serde::Serialize;
+use adventofcode_2023::Solution;
+use adventofcode_2023::cli::{self, Arguments, CliError, Format};
+use adventofcode_2023::cube_conundrum::{self, Day2, Game, MaxBallsConstraint, PUZZLE_CONSTRAINTS};
+use adventofcode_2023::game_query::GameQuery;
+use adventofcode_2023::solution::{self, Answer, Part};
 
-mod cube_conundrum;
+const USAGE: &str = "day2 [-v|-vv|-q] [--constraints \"12 red, 13 green, 14 blue\" | --constraints-file <file path>] [--part 1|2|both] [--format text|json] [file paths, directories or - for stdin]
+\tday2 [-v|-vv|-q] query '<expression>' [--format text|json] [file paths, directories or - for stdin]";
+
+/// A violated constraint, printed as one JSON object per line next to the answers and told apart by its `type`.
+#[derive(Serialize)]
+struct ViolationRecord<'a> {
+    r#type: &'static str,
+    day: u8,
+    input: String,
+    game: u32,
+    kind: &'a str,
+    count: u32,
+    max_count: u32
+}
+
+/// A game matching a query, printed as one JSON object per line.
+#[derive(Serialize)]
+struct MatchRecord {
+    day: u8,
+    input: String,
+    game: u32
+}
 
 fn main() {
-    let mut command_args = env::args();
-    _ = command_args.next();
-    let first_arg = command_args.next();
-
-    match first_arg {
-        None => eprintln!("Invalid format! Run day2 like this:\n\n\tday2 <file path>"),
-        Some(first_arg) => {
-            let file_path = path::Path::new(&first_arg);
-
-            if !file_path.exists() {
-                eprintln!("Input file {} doesn't exist!", file_path.display());
-                return;
+    if let Err(error) = run() {
+        cli::exit_with_error(error);
+    }
+}
+
+fn run() -> Result<(), CliError> {
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("query") {
+        return query(args.skip(1));
+    }
+
+    let arguments = Arguments::parse(args, &["--constraints", "--constraints-file", "--part", "--format"], USAGE)?;
+    cli::init_logging(arguments.log_level());
+    let parts = arguments.parts()?;
+    let format = arguments.format()?;
+    let (constraints_source, constraints) = constraints(&arguments)?;
+    let constraints = MaxBallsConstraint::parse_all(&constraints)
+        .map_err(|error| CliError::InvalidInput(constraints_source, error))?;
+    let solver = solution::solver::<Day2>();
+    let file_paths = cli::resolve_inputs(Day2::DAY, arguments.positional())?;
+
+    cli::for_each_input(&file_paths, |file_path| {
+        let input = cli::read_input(file_path)?;
+        let games = Day2::parse(&input).map_err(|error| CliError::InvalidInput(file_path.to_path_buf(), error))?;
+
+        let mut answers = Vec::<Answer>::new();
+        if parts.contains(Part::One) {
+            let start = Instant::now();
+            let id_sum = cube_conundrum::possible_games_id_sum(&games, &constraints);
+            let id_sum = Some(id_sum.to_string());
+            answers.push(Answer { part: Part::One, value: id_sum, duration: start.elapsed(), warnings: vec![] });
+        }
+        if parts.contains(Part::Two) {
+            let start = Instant::now();
+            let power_sum = Day2::part2(&games).map(|power_sum| power_sum.to_string());
+            answers.push(Answer { part: Part::Two, value: power_sum, duration: start.elapsed(), warnings: vec![] });
+        }
+
+        cli::print_answers(solver.as_ref(), file_path, &answers, format);
+        // The violations explain part 1, so they only come with it.
+        if parts.contains(Part::One) {
+            print_violations(file_path, &games, &constraints, format);
+        }
+        Ok(())
+    })
+}
+
+/// Prints the ids of the games matching the query given as first positional argument.
+fn query(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let arguments = Arguments::parse(args, &["--format"], USAGE)?;
+    cli::init_logging(arguments.log_level());
+    let format = arguments.format()?;
+    let (query, paths) = arguments
+        .positional()
+        .split_first()
+        .ok_or_else(|| CliError::InvalidFormat(String::from(USAGE)))?;
+    let query = GameQuery::parse(query).map_err(|error| CliError::InvalidInput(PathBuf::from("query"), error))?;
+    let file_paths = cli::resolve_inputs(Day2::DAY, paths)?;
+
+    cli::for_each_input(&file_paths, |file_path| {
+        let input = cli::read_input(file_path)?;
+        let games = Day2::parse(&input).map_err(|error| CliError::InvalidInput(file_path.to_path_buf(), error))?;
+
+        for id in query.matching_ids(&games) {
+            match format {
+                Format::Text if file_paths.len() > 1 => println!("{}: {id}", cli::input_name(file_path)),
+                Format::Text => println!("{id}"),
+                Format::Json => println!("{}", serde_json::to_string(&MatchRecord {
+                    day: Day2::DAY,
+                    input: cli::input_name(file_path),
+                    game: id
+                }).unwrap())
             }
+        }
+        Ok(())
+    })
+}
 
-            let file_content = fs::read_to_string(&file_path);
-            match file_content {
-                Ok(file_content) => {
-                    let game_id_sum = file_content
-                        .lines()
-                        .map(Game::from_line)
-                        .filter_map(|game| match game {
-                            Ok(game) => Some(game),
-                            Err(_) => None
-                        })
-                        .map(|game| game.get_minimum_required_set())
-                        .map(|cubes_set| cubes_set.power())
-                        .sum::<u32>();
-                    println!("Sum of minimum required sets' powers: {game_id_sum}")
-                }
-                Err(error) => eprintln!("Can't read input file {}: {error}", file_path.display())
+/// The constraints text and where it came from, for errors.
+fn constraints(arguments: &Arguments) -> Result<(PathBuf, String), CliError> {
+    match (arguments.option("--constraints"), arguments.option("--constraints-file")) {
+        (Some(_), Some(_)) => Err(CliError::InvalidFormat(String::from(USAGE))),
+        (Some(constraints), None) => Ok((PathBuf::from("--constraints"), String::from(constraints))),
+        (None, Some(file_path)) => {
+            let file_path = PathBuf::from(file_path);
+            let constraints = cli::read_input(&file_path)?;
+            Ok((file_path, constraints))
+        }
+        (None, None) => Ok((PathBuf::from("--constraints"), String::from(PUZZLE_CONSTRAINTS)))
+    }
+}
+
+fn print_violations(file_path: &Path, games: &[Game], constraints: &[MaxBallsConstraint], format: Format) {
+    for game in games {
+        let violations = game.violations(constraints);
+        if violations.is_empty() {
+            continue;
+        }
+
+        match format {
+            Format::Text => {
+                let violations = violations
+                    .iter()
+                    .map(|violation| format!("{} {} (at most {})", violation.count, violation.kind, violation.max_count))
+                    .collect::<Vec<String>>();
+                println!("Game {} is impossible: {}", game.id, violations.join(", "));
+            }
+            Format::Json => for violation in violations {
+                println!("{}", serde_json::to_string(&ViolationRecord {
+                    r#type: "violation",
+                    day: Day2::DAY,
+                    input: cli::input_name(file_path),
+                    game: game.id,
+                    kind: violation.kind,
+                    count: violation.count,
+                    max_count: violation.max_count
+                }).unwrap());
             }
         }
     }
@@ -0,0 +1,494 @@
+use crate::cube_conundrum::{CubesSet, Day2, Game};
+use crate::parse_error::ParseError;
+use crate::solution::Solution;
+
+/// A predicate over games, e.g. `any(red > 10) and sets < 3`.
+///
+/// Comparisons take numbers and the values of a game: `id`, `sets` (the number of sets), `power` (the power of the
+/// minimum required set) and `min(red)` (the cubes of a kind in the minimum required set). `any(...)` and `all(...)`
+/// check a [SetQuery] against the sets of the game. Comparisons combine with `not`, `and` and `or`, in that order of
+/// precedence, and parentheses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameQuery {
+    expression: Expression
+}
+
+/// A predicate over the cubes shown at once, e.g. `red > 10 or "light blue" >= 2`.
+///
+/// Comparisons take numbers, `power` and the count of a kind of cubes, which is 0 if the set doesn't show any.
+/// Kinds that aren't a single word, or that are named like a keyword, are quoted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetQuery {
+    expression: Expression
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expression {
+    Or(Box<Expression>, Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Compare(Operand, Comparison, Operand),
+    /// Only in games, the expression applies to sets.
+    Any(Box<Expression>),
+    All(Box<Expression>)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Operand {
+    Number(u32),
+    Id,
+    Sets,
+    Power,
+    MinCount(String),
+    Count(String)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Game,
+    Set
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Number(u32),
+    Word,
+    Quoted,
+    Open,
+    Close,
+    Comparison(Comparison),
+    End
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: TokenKind,
+    /// The token in the query, without the quotes for quoted kinds.
+    text: &'a str
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<Token<'a>>,
+    position: usize
+}
+
+const KEYWORDS: [&str; 8] = ["and", "or", "not", "any", "all", "id", "sets", "power"];
+
+impl GameQuery {
+    pub fn parse(query: &str) -> Result<Self, ParseError> {
+        Ok(GameQuery { expression: Parser::new(query)?.parse(Scope::Game)? })
+    }
+
+    pub fn matches(&self, game: &Game) -> bool {
+        self.expression.matches_game(game)
+    }
+
+    /// Ids of the matching `games`, in their order.
+    pub fn matching_ids(&self, games: &[Game]) -> Vec<u32> {
+        games
+            .iter()
+            .filter(|game| self.matches(game))
+            .map(|game| game.id)
+            .collect::<Vec<u32>>()
+    }
+}
+
+impl SetQuery {
+    pub fn parse(query: &str) -> Result<Self, ParseError> {
+        Ok(SetQuery { expression: Parser::new(query)?.parse(Scope::Set)? })
+    }
+
+    pub fn matches(&self, set: &CubesSet) -> bool {
+        self.expression.matches_set(set)
+    }
+}
+
+impl Comparison {
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "<" => Some(Comparison::Less),
+            "<=" => Some(Comparison::LessOrEqual),
+            ">" => Some(Comparison::Greater),
+            ">=" => Some(Comparison::GreaterOrEqual),
+            "==" => Some(Comparison::Equal),
+            "!=" => Some(Comparison::NotEqual),
+            _ => None
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Equal => "==",
+            Comparison::NotEqual => "!="
+        }
+    }
+
+    pub fn holds(&self, left: u32, right: u32) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right
+        }
+    }
+}
+
+impl Expression {
+    fn matches_game(&self, game: &Game) -> bool {
+        match self {
+            Expression::Or(left, right) => left.matches_game(game) || right.matches_game(game),
+            Expression::And(left, right) => left.matches_game(game) && right.matches_game(game),
+            Expression::Not(expression) => !expression.matches_game(game),
+            Expression::Compare(left, comparison, right) => comparison.holds(left.game_value(game), right.game_value(game)),
+            Expression::Any(expression) => game.sets().iter().any(|set| expression.matches_set(set)),
+            Expression::All(expression) => game.sets().iter().all(|set| expression.matches_set(set))
+        }
+    }
+
+    fn matches_set(&self, set: &CubesSet) -> bool {
+        match self {
+            Expression::Or(left, right) => left.matches_set(set) || right.matches_set(set),
+            Expression::And(left, right) => left.matches_set(set) && right.matches_set(set),
+            Expression::Not(expression) => !expression.matches_set(set),
+            Expression::Compare(left, comparison, right) => comparison.holds(left.set_value(set), right.set_value(set)),
+            Expression::Any(_) | Expression::All(_) => unreachable!("Sets can't contain sets!")
+        }
+    }
+}
+
+impl Operand {
+    fn game_value(&self, game: &Game) -> u32 {
+        match self {
+            Operand::Number(number) => *number,
+            Operand::Id => game.id,
+            Operand::Sets => game.sets().len() as u32,
+            Operand::Power => game.get_minimum_required_set().power(),
+            Operand::MinCount(kind) => game.get_minimum_required_set().count(kind),
+            Operand::Count(_) => unreachable!("Cube counts only exist in sets!")
+        }
+    }
+
+    fn set_value(&self, set: &CubesSet) -> u32 {
+        match self {
+            Operand::Number(number) => *number,
+            Operand::Power => set.power(),
+            Operand::Count(kind) => set.count(kind),
+            Operand::Id | Operand::Sets | Operand::MinCount(_) => unreachable!("Sets have no game values!")
+        }
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn new(query: &'a str) -> Result<Self, ParseError> {
+        Ok(Parser { query, tokens: tokenize(query)?, position: 0 })
+    }
+
+    fn parse(mut self, scope: Scope) -> Result<Expression, ParseError> {
+        let expression = self.parse_or(scope)?;
+        let token = self.next();
+        match token.kind {
+            TokenKind::End => Ok(expression),
+            _ => Err(self.error(token, "Expected 'and', 'or' or the end of the query!"))
+        }
+    }
+
+    fn parse_or(&mut self, scope: Scope) -> Result<Expression, ParseError> {
+        let mut expression = self.parse_and(scope)?;
+        while self.next_if_keyword("or") {
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and(scope)?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self, scope: Scope) -> Result<Expression, ParseError> {
+        let mut expression = self.parse_not(scope)?;
+        while self.next_if_keyword("and") {
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_not(scope)?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_not(&mut self, scope: Scope) -> Result<Expression, ParseError> {
+        let token = self.peek();
+        match (token.kind, token.text) {
+            (TokenKind::Word, "not") => {
+                self.next();
+                Ok(Expression::Not(Box::new(self.parse_not(scope)?)))
+            }
+            (TokenKind::Open, _) => {
+                self.next();
+                let expression = self.parse_or(scope)?;
+                self.expect_close()?;
+                Ok(expression)
+            }
+            (TokenKind::Word, "any" | "all") => {
+                self.next();
+                if scope == Scope::Set {
+                    return Err(self.error(token, "'any' and 'all' only apply to games!"));
+                }
+
+                self.expect_open()?;
+                let expression = Box::new(self.parse_or(Scope::Set)?);
+                self.expect_close()?;
+                if token.text == "any" {
+                    Ok(Expression::Any(expression))
+                } else {
+                    Ok(Expression::All(expression))
+                }
+            }
+            _ => self.parse_comparison(scope)
+        }
+    }
+
+    fn parse_comparison(&mut self, scope: Scope) -> Result<Expression, ParseError> {
+        let left = self.parse_operand(scope)?;
+        let token = self.next();
+        let comparison = match token.kind {
+            TokenKind::Comparison(comparison) => comparison,
+            _ => return Err(self.error(token, "Expected a comparison: <, <=, >, >=, == or !=!"))
+        };
+        let right = self.parse_operand(scope)?;
+        Ok(Expression::Compare(left, comparison, right))
+    }
+
+    fn parse_operand(&mut self, scope: Scope) -> Result<Operand, ParseError> {
+        let token = self.next();
+        match (scope, token.kind, token.text) {
+            (_, TokenKind::Number(number), _) => Ok(Operand::Number(number)),
+            (_, TokenKind::Word, "power") => Ok(Operand::Power),
+            (Scope::Game, TokenKind::Word, "id") => Ok(Operand::Id),
+            (Scope::Game, TokenKind::Word, "sets") => Ok(Operand::Sets),
+            (Scope::Game, TokenKind::Word, "min") => {
+                self.expect_open()?;
+                let kind = self.next();
+                let kind = match kind.kind {
+                    TokenKind::Word | TokenKind::Quoted => kind.text,
+                    _ => return Err(self.error(kind, "Expected a kind of cubes!"))
+                };
+                self.expect_close()?;
+                Ok(Operand::MinCount(String::from(kind)))
+            }
+            (Scope::Game, TokenKind::Word | TokenKind::Quoted, _) => Err(self.error(token,
+                "Expected 'id', 'sets', 'power', 'min(<kind>)' or a number, cube counts of sets go in 'any(...)' or 'all(...)'!")),
+            (Scope::Set, TokenKind::Quoted, kind) => Ok(Operand::Count(String::from(kind))),
+            (Scope::Set, TokenKind::Word, kind) if !KEYWORDS.contains(&kind) => Ok(Operand::Count(String::from(kind))),
+            (Scope::Set, _, _) => Err(self.error(token, "Expected 'power', a kind of cubes or a number!")),
+            (Scope::Game, _, _) => Err(self.error(token, "Expected 'id', 'sets', 'power', 'min(<kind>)' or a number!"))
+        }
+    }
+
+    fn expect_open(&mut self) -> Result<(), ParseError> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Open => Ok(()),
+            _ => Err(self.error(token, "Expected '('!"))
+        }
+    }
+
+    fn expect_close(&mut self) -> Result<(), ParseError> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Close => Ok(()),
+            _ => Err(self.error(token, "Expected ')'!"))
+        }
+    }
+
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        let token = self.peek();
+        if token.kind == TokenKind::Word && token.text == keyword {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> Token<'a> {
+        self.tokens[self.position]
+    }
+
+    /// The next token, the end token is returned forever.
+    fn next(&mut self) -> Token<'a> {
+        let token = self.tokens[self.position];
+        if token.kind != TokenKind::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error(&self, token: Token, reason: &str) -> ParseError {
+        let reason = match token.kind {
+            TokenKind::End => format!("Unexpected end of query! {reason}"),
+            _ => String::from(reason)
+        };
+        ParseError::new(Day2::DAY, self.query, token.text, reason)
+    }
+}
+
+/// Splits `query` into tokens ending with an end token, whose text is the empty end of `query`.
+fn tokenize(query: &str) -> Result<Vec<Token<'_>>, ParseError> {
+    let mut tokens = Vec::<Token>::new();
+    let bytes = query.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let start = index;
+        let byte = bytes[index];
+        let kind = match byte {
+            b' ' | b'\t' | b'\r' | b'\n' => {
+                index += 1;
+                continue;
+            }
+            b'(' => {
+                index += 1;
+                TokenKind::Open
+            }
+            b')' => {
+                index += 1;
+                TokenKind::Close
+            }
+            b'<' | b'>' | b'=' | b'!' => {
+                index += 1;
+                if bytes.get(index) == Some(&b'=') {
+                    index += 1;
+                }
+                match Comparison::from_symbol(&query[start..index]) {
+                    None => return Err(ParseError::new(Day2::DAY, query, &query[start..index], "Unknown comparison, expected <, <=, >, >=, == or !=!")),
+                    Some(comparison) => TokenKind::Comparison(comparison)
+                }
+            }
+            b'"' | b'\'' => {
+                let end = match query[start + 1..].find(byte as char) {
+                    None => return Err(ParseError::new(Day2::DAY, query, &query[start..], "Quoted kind of cubes is never closed!")),
+                    Some(length) => start + 1 + length
+                };
+                index = end + 1;
+                tokens.push(Token { kind: TokenKind::Quoted, text: &query[start + 1..end] });
+                continue;
+            }
+            b'0'..=b'9' => {
+                while index < bytes.len() && bytes[index].is_ascii_digit() {
+                    index += 1;
+                }
+                let number = &query[start..index];
+                match number.parse::<u32>() {
+                    Ok(number) => TokenKind::Number(number),
+                    Err(error) => return Err(ParseError::new(Day2::DAY, query, number, format!("Unable to parse number! {error}")))
+                }
+            }
+            _ if is_word_byte(byte) => {
+                while index < bytes.len() && is_word_byte(bytes[index]) {
+                    index += 1;
+                }
+                TokenKind::Word
+            }
+            _ => {
+                let length = query[start..].chars().next().map_or(1, char::len_utf8);
+                return Err(ParseError::new(Day2::DAY, query, &query[start..start + length], "Unexpected character!"));
+            }
+        };
+        tokens.push(Token { kind, text: &query[start..index] });
+    }
+
+    tokens.push(Token { kind: TokenKind::End, text: &query[query.len()..] });
+    Ok(tokens)
+}
+
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-' || byte >= 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAMES: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+Game 6: 11 red, 2 light blue";
+
+    fn matching_ids(query: &str) -> Vec<u32> {
+        let games = Game::extract(GAMES).unwrap();
+        GameQuery::parse(query).unwrap().matching_ids(&games)
+    }
+
+    #[test]
+    fn test_game_values() {
+        assert_eq!(matching_ids("id <= 2"), vec![1, 2]);
+        assert_eq!(matching_ids("sets == 3"), vec![1, 2, 3, 4]);
+        assert_eq!(matching_ids("power > 100"), vec![3, 4]);
+        assert_eq!(matching_ids("min(green) >= 3"), vec![2, 3, 4, 5]);
+        assert_eq!(matching_ids("min(\"light blue\") != 0"), vec![6]);
+        assert_eq!(matching_ids("5 < id"), vec![6]);
+    }
+
+    #[test]
+    fn test_sets() {
+        assert_eq!(matching_ids("any(red > 10) and sets < 3"), vec![6]);
+        assert_eq!(matching_ids("any(red > 10)"), vec![3, 4, 6]);
+        assert_eq!(matching_ids("all(blue > 0)"), vec![2, 5]);
+        assert_eq!(matching_ids("any(power == 12)"), vec![1, 2]);
+        assert_eq!(matching_ids("any('light blue' == 2 and red == 11)"), vec![6]);
+
+        let set = "3 blue, 4 red".parse::<CubesSet>().unwrap();
+        assert!(SetQuery::parse("blue == 3 and green == 0 and power == 12").unwrap().matches(&set));
+        assert!(!SetQuery::parse("red < 4").unwrap().matches(&set));
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(matching_ids("id == 1 or id == 2 and sets == 2"), vec![1]);
+        assert_eq!(matching_ids("(id == 1 or id == 2) and sets == 3"), vec![1, 2]);
+        assert_eq!(matching_ids("not id == 1 and not id > 2"), vec![2]);
+        assert_eq!(matching_ids("not (id == 1 or id > 2)"), vec![2]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = GameQuery::parse("any(red > 10) and red < 3").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (19, "red"));
+
+        let error = GameQuery::parse("sets < ").unwrap_err();
+        assert_eq!(error.column, 8);
+        assert!(error.reason.starts_with("Unexpected end of query!"));
+
+        let error = GameQuery::parse("any(red > 1").unwrap_err();
+        assert_eq!(error.reason, "Unexpected end of query! Expected ')'!");
+
+        let error = GameQuery::parse("sets = 2").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (6, "="));
+
+        let error = GameQuery::parse("sets == 2 sets").unwrap_err();
+        assert_eq!(error.reason, "Expected 'and', 'or' or the end of the query!");
+
+        let error = SetQuery::parse("any(red > 1)").unwrap_err();
+        assert_eq!(error.reason, "'any' and 'all' only apply to games!");
+
+        assert!(SetQuery::parse("sets > 1").is_err());
+        assert!(SetQuery::parse("red > 'blue").is_err());
+        assert!(GameQuery::parse("id > 99999999999").is_err());
+        assert!(GameQuery::parse("id > 1 & id < 3").is_err());
+        assert!(GameQuery::parse("").is_err());
+    }
+}
\ No newline at end of file
@@ -1,34 +1,45 @@
 // https://adventofcode.com/2023/day/3
 
-use std::{env, fs, path};
-use crate::gear_ratios::{GetPartNumbers, SchematicPart};
+use std::env;
+use adventofcode_2023::Solution;
+use adventofcode_2023::cli::{self, Arguments, CliError};
+use adventofcode_2023::gear_ratios::{Day3, SchematicPart};
+use adventofcode_2023::schematic_render::{RenderFormat, SchematicRenderer};
 
-mod gear_ratios;
+const USAGE: &str = "day3 [-v|-vv|-q] [--part 1|2|both] [--format text|json] [file paths, directories or - for stdin]
+\tday3 [-v|-vv|-q] render [--html] [--legend] [--statistics] [file paths, directories or - for stdin]";
 
 fn main() {
-    let mut command_args = env::args();
-    _ = command_args.next();
-    let first_arg = command_args.next();
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) != Some("render") {
+        cli::run_day(Day3::DAY);
+        return;
+    }
+
+    if let Err(error) = render(args.skip(1)) {
+        cli::exit_with_error(error);
+    }
+}
 
-    match first_arg {
-        None => eprintln!("Invalid format! Run day2 like this:\n\n\tday2 <file path>"),
-        Some(first_arg) => {
-            let file_path = path::Path::new(&first_arg);
+/// Prints each schematic with its part numbers, other numbers and gears coloured.
+fn render(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    let arguments = Arguments::parse_with_flags(args, &[], &["--html", "--legend", "--statistics"], USAGE)?;
+    cli::init_logging(arguments.log_level());
+    let format = if arguments.flag("--html") { RenderFormat::Html } else { RenderFormat::Ansi };
+    let file_paths = cli::resolve_inputs(Day3::DAY, arguments.positional())?;
 
-            if !file_path.exists() {
-                eprintln!("Input file {} doesn't exist!", file_path.display());
-                return;
-            }
+    cli::for_each_input(&file_paths, |file_path| {
+        let input = cli::read_input(file_path)?;
+        let schematic = SchematicPart::extract(&input).map_err(|error| CliError::InvalidInput(file_path.to_path_buf(), error))?;
 
-            let file_content = fs::read_to_string(&file_path);
-            match file_content {
-                Ok(file_content) => {
-                    let parts = SchematicPart::extract(&file_content);
-                    println!("Sum of part numbers is {}", parts.get_part_numbers().iter().sum::<u32>());
-                    println!("Sum of gear values is {}", parts.get_gear_values().iter().sum::<u32>());
-                },
-                Err(error) => eprintln!("Can't read file {}!\n{error}", file_path.display())
-            }
+        let mut renderer = SchematicRenderer::new(&schematic);
+        if arguments.flag("--legend") {
+            renderer = renderer.with_legend();
         }
-    }
-}
+        if arguments.flag("--statistics") {
+            renderer = renderer.with_statistics();
+        }
+        print!("{}", renderer.render(format));
+        Ok(())
+    })
+}
\ No newline at end of file
@@ -1,8 +1,12 @@
-#[derive(Debug, Eq, PartialEq)]
-pub struct Coords (u32, u32);
+use crate::parse_error::ParseError;
+use crate::solution::Solution;
+
+/// Column and row of a cell, 0-based.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Coords (pub u32, pub u32);
 
 impl Coords {
-    fn left(self: &Self) -> u32 {
+    fn left(&self) -> u32 {
         if self.0 > 0 {
             self.0 - 1
         } else {
@@ -10,11 +14,12 @@ impl Coords {
         }
     }
 
-    fn right(self: &Self, order_of_magnitude: u32) -> u32 {
-        self.0 + 1 + order_of_magnitude
+    /// Column right of a part starting here that is `width` cells wide.
+    fn right(&self, width: u32) -> u32 {
+        self.0 + width
     }
 
-    fn up(self: &Self) -> u32 {
+    fn up(&self) -> u32 {
         if self.1 > 0 {
             self.1 - 1
         } else {
@@ -22,16 +27,18 @@ impl Coords {
         }
     }
 
-    fn down(self: &Self) -> u32 {
+    fn down(&self) -> u32 {
         self.1 + 1
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum SchematicPart {
+    /// A number, `position` is its first digit and `text` its digits as written, leading zeros included.
     Number {
-        value: u32,
-        position: Coords
+        value: u64,
+        position: Coords,
+        text: String
     },
     Symbol {
         kind: char,
@@ -40,144 +47,439 @@ pub enum SchematicPart {
 }
 
 impl SchematicPart {
-    pub fn extract(schematic: &str) -> Vec<SchematicPart> {
+    pub fn extract(schematic: &str) -> Result<Schematic, ParseError> {
         let mut schematic_parts = Vec::<SchematicPart>::new();
+        let mut row_widths = Vec::<usize>::new();
         for (y, line) in schematic.lines().enumerate() {
-            let mut number_value = 0;
-            let mut number_x = 0;
+            SchematicPart::extract_line(line, y as u32, &mut schematic_parts).map_err(|error| error.on_line(y))?;
+            row_widths.push(line.chars().count());
+        }
 
-            for (x, char) in line.chars().enumerate() {
-                if let Some(digit) = char.to_digit(10) {
-                    if number_value == 0 {
-                        number_x = x;
-                    }
+        Ok(Schematic::with_row_widths(schematic_parts, row_widths))
+    }
+
+    /// Appends the parts of `line`, numbers are runs of ASCII digits and symbols are anything else but `.`.
+    fn extract_line(line: &str, y: u32, schematic_parts: &mut Vec<SchematicPart>) -> Result<(), ParseError> {
+        // Column and byte index of the first digit of the number being read.
+        let mut number_start = None::<(usize, usize)>;
+
+        for (x, (index, char)) in line.char_indices().enumerate() {
+            if char.is_ascii_digit() {
+                number_start.get_or_insert((x, index));
+                continue;
+            }
+
+            if let Some((number_x, number_index)) = number_start.take() {
+                schematic_parts.push(SchematicPart::number(line, &line[number_index..index], Coords(number_x as u32, y))?);
+            }
+
+            if char != '.' {
+                schematic_parts.push(SchematicPart::Symbol {
+                    kind: char,
+                    position: Coords (x as u32, y)
+                });
+            }
+        }
 
-                    number_value = number_value * 10 + digit;
+        if let Some((number_x, number_index)) = number_start {
+            schematic_parts.push(SchematicPart::number(line, &line[number_index..], Coords(number_x as u32, y))?);
+        }
+
+        Ok(())
+    }
+
+    /// A number written as `text`, which should be a slice of `line`.
+    pub(crate) fn number(line: &str, text: &str, position: Coords) -> Result<SchematicPart, ParseError> {
+        match text.parse::<u64>() {
+            Ok(value) => Ok(SchematicPart::Number { value, position, text: String::from(text) }),
+            Err(error) => Err(ParseError::new(Day3::DAY, line, text, format!("Unable to parse number! {error}")))
+        }
+    }
+
+    pub fn position(&self) -> &Coords {
+        match self {
+            SchematicPart::Number { position, .. } | SchematicPart::Symbol { position, .. } => position
+        }
+    }
+
+    /// Number of cells covered by the part, the digits of a number including its leading zeros.
+    pub fn width(&self) -> u32 {
+        match self {
+            SchematicPart::Number { text, .. } => text.len() as u32,
+            SchematicPart::Symbol { .. } => 1
+        }
+    }
+
+    /// The character of the part in column `x`, which should be covered by the part.
+    pub fn char_at(&self, x: u32) -> char {
+        match self {
+            SchematicPart::Number { text, position, .. } => text.as_bytes()[(x - position.0) as usize] as char,
+            SchematicPart::Symbol { kind, .. } => *kind
+        }
+    }
+
+    /// First column, last column and row of the cells covered by the part.
+    fn cells(&self) -> (usize, usize, usize) {
+        let position = self.position();
+        let x = position.0 as usize;
+        (x, x + self.width() as usize - 1, position.1 as usize)
+    }
+}
+
+/// Parts of an engine schematic, indexed by the cells they cover so that finding adjacent parts only looks at the
+/// neighbouring cells.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Schematic {
+    parts: Vec<SchematicPart>,
+    /// Index in `cells` of the first cell of each row, followed by the number of cells.
+    row_starts: Vec<usize>,
+    /// Index in `parts` of the part covering each cell.
+    cells: Vec<u32>
+}
+
+const NO_PART: u32 = u32::MAX;
+
+impl Schematic {
+    /// Indexes `parts`, a part covers the cells of the parts before it if they overlap.
+    ///
+    /// Rows only go up to their last part.
+    pub fn new(parts: Vec<SchematicPart>) -> Self {
+        Schematic::with_row_widths(parts, Vec::<usize>::new())
+    }
+
+    /// Indexes `parts` in rows at least `row_widths` cells wide, the lines they were extracted from.
+    fn with_row_widths(parts: Vec<SchematicPart>, mut row_widths: Vec<usize>) -> Self {
+        for part in &parts {
+            let (_, last_x, y) = part.cells();
+            if row_widths.len() <= y {
+                row_widths.resize(y + 1, 0);
+            }
+            row_widths[y] = row_widths[y].max(last_x + 1);
+        }
+
+        let mut row_starts = Vec::<usize>::with_capacity(row_widths.len() + 1);
+        row_starts.push(0);
+        for width in row_widths {
+            row_starts.push(row_starts[row_starts.len() - 1] + width);
+        }
+
+        let mut cells = vec![NO_PART; row_starts[row_starts.len() - 1]];
+        for (index, part) in parts.iter().enumerate() {
+            let (first_x, last_x, y) = part.cells();
+            cells[row_starts[y] + first_x..=row_starts[y] + last_x].fill(index as u32);
+        }
+
+        Schematic { parts, row_starts, cells }
+    }
+
+    pub fn parts(&self) -> &[SchematicPart] {
+        &self.parts
+    }
+
+    pub fn rows(&self) -> usize {
+        self.row_starts.len() - 1
+    }
+
+    /// Number of cells in row `y`, 0 past the last row.
+    pub fn row_width(&self, y: usize) -> usize {
+        if y >= self.rows() {
+            return 0;
+        }
+
+        self.row_starts[y + 1] - self.row_starts[y]
+    }
+
+    /// Whether `part` is a number adjacent to a symbol.
+    pub fn is_part_number(&self, part: &SchematicPart) -> bool {
+        match part {
+            SchematicPart::Number { position, .. } => self
+                .part_indexes_around(position, part.width())
+                .any(|index| matches!(self.parts[index], SchematicPart::Symbol { .. })),
+            SchematicPart::Symbol { .. } => false
+        }
+    }
+
+    /// The distinct numbers adjacent to the cell at `position`, in the order of the schematic.
+    pub fn adjacent_numbers(&self, position: &Coords) -> Vec<GearNumber> {
+        let mut adjacent_numbers = self
+            .part_indexes_around(position, 1)
+            .filter(|&index| matches!(self.parts[index], SchematicPart::Number { .. }))
+            .collect::<Vec<usize>>();
+        adjacent_numbers.sort_unstable();
+        adjacent_numbers.dedup();
+
+        adjacent_numbers
+            .into_iter()
+            .filter_map(|index| match self.parts[index] {
+                SchematicPart::Number { value, position, .. } => Some(GearNumber { value, position }),
+                SchematicPart::Symbol { .. } => None
+            })
+            .collect::<Vec<GearNumber>>()
+    }
+
+    /// The part covering the cell at `x`, `y`.
+    pub fn part_at(&self, x: u32, y: u32) -> Option<&SchematicPart> {
+        self.part_index_at(x, y).map(|index| &self.parts[index])
+    }
+
+    /// Index in [Schematic::parts] of the part covering the cell at `x`, `y`.
+    pub fn part_index_at(&self, x: u32, y: u32) -> Option<usize> {
+        let y = y as usize;
+        if y + 1 >= self.row_starts.len() {
+            return None;
+        }
+
+        let cell = self.row_starts[y] + x as usize;
+        if cell >= self.row_starts[y + 1] {
+            return None;
+        }
+
+        match self.cells[cell] {
+            NO_PART => None,
+            index => Some(index as usize)
+        }
+    }
+
+    /// Symbols matching `rule`, with the numbers adjacent to them, in the order of the schematic.
+    pub fn gears(&self, rule: &GearRule) -> Vec<Gear> {
+        let mut gears = Vec::<Gear>::new();
+
+        for part in &self.parts {
+            if let SchematicPart::Symbol { kind, position } = part {
+                if !rule.symbols.contains(kind) {
                     continue;
                 }
 
-                if number_value > 0 {
-                    schematic_parts.push(SchematicPart::Number {
-                        value: number_value,
-                        position: Coords(number_x as u32, y as u32)
-                    });
-                    number_value = 0;
-                    number_x = 0;
+                let numbers = self.adjacent_numbers(position);
+                if !rule.neighbours.allows(numbers.len()) {
+                    continue;
                 }
 
-                if char != '.' {
-                    schematic_parts.push(SchematicPart::Symbol {
-                        kind: char,
-                        position: Coords (x as u32, y as u32)
-                    });
-                }
+                gears.push(Gear { kind: *kind, position: *position, numbers });
             }
+        }
 
-            if number_value > 0 {
-                schematic_parts.push(SchematicPart::Number {
-                    value: number_value,
-                    position: Coords(number_x as u32, y as u32)
-                });
-            }
+        gears
+    }
+
+    /// Values of the gears matching `rule`, combined as the rule says, `None` if one doesn't fit in a `u64`.
+    pub fn gear_values(&self, rule: &GearRule) -> Option<Vec<u64>> {
+        let mut gear_values = Vec::<u64>::new();
+        for gear in self.gears(rule) {
+            gear_values.extend(gear.combine(rule.combine)?);
         }
 
-        schematic_parts
+        Some(gear_values)
+    }
+
+    /// Indexes of the parts covering the cells around a part at `position` that is `width` cells wide, including the
+    /// part itself. Parts wider than a cell are found once per covered cell.
+    fn part_indexes_around(&self, position: &Coords, width: u32) -> impl Iterator<Item = usize> + '_ {
+        let (left, right) = (position.left(), position.right(width));
+        (position.up()..=position.down())
+            .flat_map(move |y| (left..=right).filter_map(move |x| self.part_index_at(x, y)))
     }
 }
 
 pub trait GetPartNumbers {
-    fn get_part_numbers(self: &Self) -> Vec<u32>;
-    fn get_gear_values(self: &Self) -> Vec<u32>;
+    fn get_part_numbers(&self) -> Vec<u64>;
+    fn get_gear_values(&self) -> Option<Vec<u64>>;
 }
 
-impl GetPartNumbers for Vec<SchematicPart> {
-    fn get_part_numbers(self: &Self) -> Vec<u32> {
-        let mut part_numbers = Vec::<u32>::new();
-
-        'outer: for part in self {
-            match part {
-                SchematicPart::Number { value: number_value, position: number_position } => {
-                    for other_part in self {
-                        match other_part {
-                            SchematicPart::Number { .. } => continue,
-                            SchematicPart::Symbol { position: symbol_position, .. } => {
-                                if is_adjacent(number_position, symbol_position, get_order_of_magnitude(*number_value)) {
-                                    part_numbers.push(*number_value);
-                                    continue 'outer;
-                                }
-                            }
-                        }
-                    }
+impl GetPartNumbers for Schematic {
+    fn get_part_numbers(&self) -> Vec<u64> {
+        let mut part_numbers = Vec::<u64>::new();
+
+        for part in &self.parts {
+            if let SchematicPart::Number { value, .. } = part {
+                if self.is_part_number(part) {
+                    part_numbers.push(*value);
                 }
-                SchematicPart::Symbol { .. } => continue
             }
         }
 
         part_numbers
     }
 
-    fn get_gear_values(self: &Self) -> Vec<u32> {
-        let mut gear_values = Vec::<u32>::new();
+    fn get_gear_values(&self) -> Option<Vec<u64>> {
+        self.gear_values(&GearRule::default())
+    }
+}
 
-        'outer: for part in self {
-            match part {
-                SchematicPart::Symbol { kind, position: symbol_position } => {
-                    if *kind != '*' {
-                        continue;
-                    }
+/// Which symbols are gears and what they're worth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearRule {
+    pub symbols: Vec<char>,
+    pub neighbours: NeighbourCount,
+    pub combine: Combine
+}
 
-                    let mut adjacent_numbers = Vec::<u32>::new();
-                    for other_part in self {
-                        match other_part {
-                            SchematicPart::Symbol { .. } => continue,
-                            SchematicPart::Number { value: number_value, position: number_position} => {
-                                if is_adjacent(number_position, symbol_position, get_order_of_magnitude(*number_value)) {
-                                    adjacent_numbers.push(*number_value);
-                                }
-
-                                if adjacent_numbers.len() > 2 {
-                                    continue 'outer;
-                                }
-                            }
-                        }
-                    }
+/// Number of distinct numbers a gear must be adjacent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeighbourCount {
+    Exactly(usize),
+    AtLeast(usize),
+    AtMost(usize)
+}
 
-                    if adjacent_numbers.len() == 2 {
-                        let number_one = adjacent_numbers.get(0).unwrap_or(&0);
-                        let number_two = adjacent_numbers.get(1).unwrap_or(&0);
-                        gear_values.push(number_one * number_two);
-                    }
-                }
-                SchematicPart::Number { .. } => continue
-            }
+/// How the adjacent numbers of a gear are turned into values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    /// The product of the numbers, the gear ratio.
+    Product,
+    Sum,
+    /// Every number as its own value.
+    List
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gear {
+    pub kind: char,
+    pub position: Coords,
+    /// The adjacent numbers, in the order of the schematic.
+    pub numbers: Vec<GearNumber>
+}
+
+/// A number adjacent to a gear, `position` is its first digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GearNumber {
+    pub value: u64,
+    pub position: Coords
+}
+
+impl GearRule {
+    pub fn new(symbols: &[char], neighbours: NeighbourCount, combine: Combine) -> Self {
+        GearRule { symbols: symbols.to_vec(), neighbours, combine }
+    }
+}
+
+/// The puzzle's rule, a `*` next to exactly two numbers is worth their product.
+impl Default for GearRule {
+    fn default() -> Self {
+        GearRule::new(&['*'], NeighbourCount::Exactly(2), Combine::Product)
+    }
+}
+
+impl NeighbourCount {
+    pub fn allows(&self, count: usize) -> bool {
+        match self {
+            NeighbourCount::Exactly(expected) => count == *expected,
+            NeighbourCount::AtLeast(min) => count >= *min,
+            NeighbourCount::AtMost(max) => count <= *max
         }
+    }
+}
 
-        gear_values
+impl Gear {
+    pub fn values(&self) -> Vec<u64> {
+        self.numbers
+            .iter()
+            .map(|number| number.value)
+            .collect::<Vec<u64>>()
+    }
+
+    /// The values of the gear, one for [Combine::Product] and [Combine::Sum], one per number for [Combine::List].
+    ///
+    /// `None` if the product or sum doesn't fit in a `u64`.
+    pub fn combine(&self, combine: Combine) -> Option<Vec<u64>> {
+        let values = self.values();
+        match combine {
+            Combine::Product => values
+                .iter()
+                .try_fold(1u64, |product, &value| product.checked_mul(value))
+                .map(|product| vec![product]),
+            Combine::Sum => checked_sum(&values).map(|sum| vec![sum]),
+            Combine::List => Some(values)
+        }
     }
 }
 
-fn is_adjacent(number_position: &Coords, symbol_position: &Coords, order_of_magnitude: u32) -> bool {
-    symbol_position.0 >= number_position.left()
-        && symbol_position.0 <= number_position.right(order_of_magnitude)
-        && symbol_position.1 >= number_position.up()
-        && symbol_position.1 <= number_position.down()
+/// Sum of `values`, `None` if it doesn't fit in a `u64`.
+fn checked_sum(values: &[u64]) -> Option<u64> {
+    values.iter().try_fold(0u64, |sum, &value| sum.checked_add(value))
 }
 
-fn get_order_of_magnitude(number: u32) -> u32 {
-    let mut remainder = number;
-    let mut order_of_magnitude = 0;
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Gear Ratios";
+
+    type Input<'a> = Schematic;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        SchematicPart::extract(input)
+    }
 
-    while remainder >= 10 {
-        remainder /= 10;
-        order_of_magnitude += 1;
+    /// `None` if the sum doesn't fit in a `u64`.
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        checked_sum(&input.get_part_numbers())
     }
 
-    order_of_magnitude
+    /// `None` if a gear value or the sum doesn't fit in a `u64`.
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        checked_sum(&input.get_gear_values()?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::{self, Rng};
+
+    fn number(text: &str, x: u32, y: u32) -> SchematicPart {
+        SchematicPart::Number { value: text.parse::<u64>().unwrap(), position: Coords(x, y), text: String::from(text) }
+    }
+
+    /// The quadratic implementation the index replaced, comparing every number with every symbol.
+    fn naive_part_numbers(parts: &[SchematicPart]) -> Vec<u64> {
+        let mut part_numbers = Vec::<u64>::new();
+
+        'outer: for part in parts {
+            if let SchematicPart::Number { value: number_value, position: number_position, .. } = part {
+                for other_part in parts {
+                    if let SchematicPart::Symbol { position: symbol_position, .. } = other_part {
+                        if is_adjacent(number_position, symbol_position, part.width()) {
+                            part_numbers.push(*number_value);
+                            continue 'outer;
+                        }
+                    }
+                }
+            }
+        }
+
+        part_numbers
+    }
+
+    fn naive_gear_values(parts: &[SchematicPart]) -> Vec<u64> {
+        let mut gear_values = Vec::<u64>::new();
+
+        for part in parts {
+            if let SchematicPart::Symbol { kind: '*', position: symbol_position } = part {
+                let adjacent_numbers = parts
+                    .iter()
+                    .filter_map(|other_part| match other_part {
+                        SchematicPart::Number { value, position, .. } if is_adjacent(position, symbol_position, other_part.width()) => Some(*value),
+                        _ => None
+                    })
+                    .collect::<Vec<u64>>();
+                if adjacent_numbers.len() == 2 {
+                    gear_values.push(adjacent_numbers[0] * adjacent_numbers[1]);
+                }
+            }
+        }
+
+        gear_values
+    }
+
+    fn is_adjacent(number_position: &Coords, symbol_position: &Coords, width: u32) -> bool {
+        symbol_position.0 >= number_position.left()
+            && symbol_position.0 <= number_position.right(width)
+            && symbol_position.1 >= number_position.up()
+            && symbol_position.1 <= number_position.down()
+    }
 
     #[test]
     fn test_extract_schematic_parts() {
@@ -191,34 +493,34 @@ mod tests {
 ......755.
 ...$.*....
 .664.598..";
-        let parts = SchematicPart::extract(schematic);
-        assert_eq!(parts, vec![
-            SchematicPart::Number { value: 467, position: Coords(0, 0) },
-            SchematicPart::Number { value: 114, position: Coords(5, 0) },
+        let schematic = SchematicPart::extract(schematic).unwrap();
+        assert_eq!(schematic.parts(), vec![
+            number("467", 0, 0),
+            number("114", 5, 0),
             SchematicPart::Symbol { kind: '*', position: Coords(3, 1) },
-            SchematicPart::Number { value: 35, position: Coords(2, 2) },
-            SchematicPart::Number { value: 633, position: Coords(6, 2) },
+            number("35", 2, 2),
+            number("633", 6, 2),
             SchematicPart::Symbol { kind: '#', position: Coords(6, 3) },
-            SchematicPart::Number { value: 617, position: Coords(0, 4) },
+            number("617", 0, 4),
             SchematicPart::Symbol { kind: '*', position: Coords(3, 4) },
             SchematicPart::Symbol { kind: '+', position: Coords(5, 5) },
-            SchematicPart::Number { value: 58, position: Coords(7, 5) },
-            SchematicPart::Number { value: 592, position: Coords(2, 6) },
-            SchematicPart::Number { value: 755, position: Coords(6, 7) },
+            number("58", 7, 5),
+            number("592", 2, 6),
+            number("755", 6, 7),
             SchematicPart::Symbol { kind: '$', position: Coords(3, 8) },
             SchematicPart::Symbol { kind: '*', position: Coords(5, 8) },
-            SchematicPart::Number { value: 664, position: Coords(1, 9) },
-            SchematicPart::Number { value: 598, position: Coords(5, 9) }]);
+            number("664", 1, 9),
+            number("598", 5, 9)]);
     }
 
     #[test]
     fn test_filter_invalid() {
-        let parts = vec![
-            SchematicPart::Number { value: 45, position: Coords(4, 5) },
+        let parts = Schematic::new(vec![
+            number("45", 4, 5),
             SchematicPart::Symbol { kind: '$', position: Coords(6, 5) },
-            SchematicPart::Number { value: 711, position: Coords(5, 6) },
-            SchematicPart::Number { value: 6, position: Coords(8, 5) }
-        ];
+            number("711", 5, 6),
+            number("6", 8, 5)
+        ]);
         let part_numbers = parts.get_part_numbers();
         assert_eq!(part_numbers, vec![45, 711]);
     }
@@ -235,179 +537,179 @@ mod tests {
 ......755.
 ...$.*....
 .664.598..";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 4361);
 
         let schematic = "467..114..
 +..*......";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 467);
 
         let schematic = "467..114..
 ....*.....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 114);
 
         let schematic = "*....
 .475.
 .....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = ".*...
 .475.
 .....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = "..*..
 .475.
 .....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = "...*.
 .475.
 .....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = "....*
 .475.
 .....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = ".....
 *475.
 .....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = ".....
 .475*
 .....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = ".....
 .475.
 *....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = ".....
 .475.
 .*...";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = ".....
 .475.
 ..*..";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = ".....
 .475.
 ...*.";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = ".....
 .475.
 ....*";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 475);
 
         let schematic = ".....
 ...1.
 ..../";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 1);
 
         let schematic = ".....
 ...42
 ..../";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 42);
 
         let schematic = ".....
 42...
 /....";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 42);
 
         let schematic = ".....
 /....
 42...";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 42);
 
         let schematic = ".....
 ../..
 10...";
-        let sum_of_part_numbers = SchematicPart::extract(schematic)
+        let sum_of_part_numbers = SchematicPart::extract(schematic).unwrap()
             .get_part_numbers()
             .iter()
-            .sum::<u32>();
+            .sum::<u64>();
         assert_eq!(sum_of_part_numbers, 10);
     }
 
@@ -423,7 +725,138 @@ mod tests {
 ......755.
 ...$.*....
 .664.598..";
-        let gear_values = SchematicPart::extract(schematic).get_gear_values().iter().sum::<u32>();
+        let gear_values = SchematicPart::extract(schematic).unwrap().get_gear_values().unwrap().iter().sum::<u64>();
         assert_eq!(gear_values, 467_835);
     }
+
+    #[test]
+    fn test_part_at() {
+        let schematic = SchematicPart::extract("467..
+...*.
+
+.0075").unwrap();
+        assert_eq!(schematic.part_at(2, 0), Some(&number("467", 0, 0)));
+        assert_eq!(schematic.part_at(3, 0), None);
+        assert_eq!(schematic.part_at(3, 1), Some(&SchematicPart::Symbol { kind: '*', position: Coords(3, 1) }));
+        assert_eq!(schematic.part_at(4, 1), None);
+        assert_eq!(schematic.part_at(0, 2), None);
+        assert_eq!(schematic.part_at(0, 3), None);
+        assert_eq!(schematic.part_at(1, 3), Some(&number("0075", 1, 3)));
+        assert_eq!(schematic.part_at(4, 3), Some(&number("0075", 1, 3)));
+        assert_eq!(schematic.part_at(0, 4), None);
+    }
+
+    #[test]
+    fn test_matches_naive_implementation() {
+        let mut rng = Rng::new(3);
+        let mut schematics = [1, 2, 5, 40]
+            .map(|size| generator::gear_ratios(&mut rng, size))
+            .to_vec();
+        for _ in 0..200 {
+            let schematic = (0..rng.between(1, 12))
+                .map(|_| (0..rng.between(0, 8))
+                    .map(|_| *rng.choose(&[".", ".", "*", "*", "#", "0.", "7", "05.", "42*", "999."]))
+                    .collect::<String>())
+                .collect::<Vec<String>>()
+                .join("\n");
+            schematics.push(schematic);
+        }
+
+        for schematic in schematics {
+            let indexed = SchematicPart::extract(&schematic).unwrap();
+            assert_eq!(indexed.get_part_numbers(), naive_part_numbers(indexed.parts()), "{schematic}");
+            assert_eq!(indexed.get_gear_values(), Some(naive_gear_values(indexed.parts())), "{schematic}");
+        }
+    }
+
+    #[test]
+    fn test_gears() {
+        let schematic = SchematicPart::extract("467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..").unwrap();
+        let gears = schematic.gears(&GearRule::default());
+        assert_eq!(gears, vec![
+            Gear {
+                kind: '*',
+                position: Coords(3, 1),
+                numbers: vec![
+                    GearNumber { value: 467, position: Coords(0, 0) },
+                    GearNumber { value: 35, position: Coords(2, 2) }
+                ]
+            },
+            Gear {
+                kind: '*',
+                position: Coords(5, 8),
+                numbers: vec![
+                    GearNumber { value: 755, position: Coords(6, 7) },
+                    GearNumber { value: 598, position: Coords(5, 9) }
+                ]
+            }
+        ]);
+        assert_eq!(gears[0].combine(Combine::Product), Some(vec![16_345]));
+        assert_eq!(gears[0].combine(Combine::Sum), Some(vec![502]));
+        assert_eq!(gears[0].combine(Combine::List), Some(vec![467, 35]));
+
+        let rule = GearRule::new(&['*'], NeighbourCount::AtMost(1), Combine::Sum);
+        assert_eq!(schematic.gear_values(&rule), Some(vec![617]));
+
+        let rule = GearRule::new(&['#', '+', '$'], NeighbourCount::AtLeast(1), Combine::List);
+        assert_eq!(schematic.gear_values(&rule), Some(vec![633, 592, 664]));
+
+        let rule = GearRule::new(&['#', '*'], NeighbourCount::Exactly(1), Combine::Product);
+        let gears = schematic.gears(&rule);
+        assert_eq!(gears.iter().map(|gear| gear.kind).collect::<String>(), "#*");
+        assert_eq!(schematic.gear_values(&rule), Some(vec![633, 617]));
+
+        let rule = GearRule::new(&['*'], NeighbourCount::AtMost(0), Combine::Product);
+        assert_eq!(SchematicPart::extract("..*\n1..").unwrap().gear_values(&rule), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_zeros() {
+        let schematic = SchematicPart::extract("0*007.\n..0...").unwrap();
+        assert_eq!(schematic.parts(), vec![
+            number("0", 0, 0),
+            SchematicPart::Symbol { kind: '*', position: Coords(1, 0) },
+            number("007", 2, 0),
+            number("0", 2, 1)
+        ]);
+        assert_eq!(schematic.parts()[2].width(), 3);
+        assert_eq!(schematic.get_part_numbers(), vec![0, 7, 0]);
+        assert_eq!(schematic.gear_values(&GearRule::new(&['*'], NeighbourCount::AtLeast(1), Combine::List)), Some(vec![0, 7, 0]));
+
+        let schematic = SchematicPart::extract("....#\n0100.").unwrap();
+        assert_eq!(schematic.get_part_numbers(), vec![100]);
+
+        let schematic = SchematicPart::extract("10.").unwrap();
+        assert_eq!(schematic.parts(), vec![number("10", 0, 0)]);
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let schematic = SchematicPart::extract("12345678901*\n...........2").unwrap();
+        assert_eq!(schematic.get_part_numbers(), vec![12_345_678_901, 2]);
+        assert_eq!(schematic.get_gear_values(), Some(vec![24_691_357_802]));
+
+        let error = SchematicPart::extract("1....\n.*123456789012345678901").unwrap_err();
+        assert_eq!((error.day, error.line, error.column), (3, 2, 3));
+        assert_eq!(error.text, "123456789012345678901");
+
+        let schematic = SchematicPart::extract("9999999999999999999*9999999999999999999").unwrap();
+        assert_eq!(Day3::part1(&schematic), None);
+        assert_eq!(Day3::part2(&schematic), None);
+
+        let schematic = SchematicPart::extract("9999999999*9999999999").unwrap();
+        assert_eq!(Day3::part1(&schematic), Some(19_999_999_998));
+        assert_eq!(Day3::part2(&schematic), None);
+        let rule = GearRule::new(&['*'], NeighbourCount::Exactly(2), Combine::Sum);
+        assert_eq!(schematic.gear_values(&rule), Some(vec![19_999_999_998]));
+    }
 }
\ No newline at end of file
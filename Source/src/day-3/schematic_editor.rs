@@ -0,0 +1,642 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use crate::gear_ratios::{Coords, Day3, Gear, GearNumber, GearRule, Schematic, SchematicPart};
+use crate::parse_error::ParseError;
+use crate::solution::Solution;
+
+/// A schematic whose cells can be changed, keeping its part numbers, gears and their sums up to date.
+///
+/// An edit only looks at the cells around the changed one: the numbers it splits or joins are extracted again, and
+/// the parts close enough to touch them are checked again.
+#[derive(Debug, Clone)]
+pub struct EditableSchematic {
+    rule: GearRule,
+    /// Parts by slot, slots of removed parts are reused.
+    parts: Vec<Option<SchematicPart>>,
+    free_slots: Vec<usize>,
+    /// Slot of the part covering each cell of each row.
+    rows: Vec<Vec<u32>>,
+    part_numbers: HashSet<usize>,
+    gears: HashMap<usize, Gear>,
+    /// Wider than the answers, so that they're only unknown while they don't fit in a `u64`.
+    part_number_sum: u128,
+    /// Sum of the gears whose value fits in a `u64`, the others are only counted.
+    gear_value_sum: u128,
+    overflowing_gears: usize
+}
+
+/// What an edit changed, parts that changed position or value are both removed and added.
+///
+/// The change of the gear value sum is `None` if a gear value doesn't fit in a `u64` before or after the edit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchematicDiff {
+    pub removed_parts: Vec<SchematicPart>,
+    pub added_parts: Vec<SchematicPart>,
+    pub removed_part_numbers: Vec<SchematicPart>,
+    pub added_part_numbers: Vec<SchematicPart>,
+    pub removed_gears: Vec<Gear>,
+    pub added_gears: Vec<Gear>,
+    pub part_number_sum_change: i128,
+    pub gear_value_sum_change: Option<i128>
+}
+
+/// Cells from `left` to `right` and `up` to `down`, inclusive.
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    left: u32,
+    right: u32,
+    up: u32,
+    down: u32
+}
+
+const NO_PART: u32 = u32::MAX;
+
+impl EditableSchematic {
+    pub fn new(schematic: &Schematic, rule: GearRule) -> Self {
+        let parts = schematic
+            .parts()
+            .iter()
+            .cloned()
+            .map(Some)
+            .collect::<Vec<Option<SchematicPart>>>();
+        let rows = (0..schematic.rows())
+            .map(|y| (0..schematic.row_width(y))
+                .map(|x| schematic.part_index_at(x as u32, y as u32).map_or(NO_PART, |index| index as u32))
+                .collect::<Vec<u32>>())
+            .collect::<Vec<Vec<u32>>>();
+
+        let mut editable = EditableSchematic {
+            rule,
+            parts,
+            free_slots: Vec::<usize>::new(),
+            rows,
+            part_numbers: HashSet::<usize>::new(),
+            gears: HashMap::<usize, Gear>::new(),
+            part_number_sum: 0,
+            gear_value_sum: 0,
+            overflowing_gears: 0
+        };
+        for slot in 0..editable.parts.len() {
+            editable.classify(slot);
+        }
+
+        editable
+    }
+
+    pub fn rule(&self) -> &GearRule {
+        &self.rule
+    }
+
+    /// `None` if the sum doesn't fit in a `u64`.
+    pub fn part_number_sum(&self) -> Option<u64> {
+        u64::try_from(self.part_number_sum).ok()
+    }
+
+    /// Sum of the values of the gears, combined as the rule says, `None` if a value or the sum doesn't fit in a `u64`.
+    pub fn gear_value_sum(&self) -> Option<u64> {
+        if self.overflowing_gears > 0 {
+            return None;
+        }
+
+        u64::try_from(self.gear_value_sum).ok()
+    }
+
+    /// Parts in reading order.
+    pub fn parts(&self) -> Vec<&SchematicPart> {
+        let mut parts = self.parts
+            .iter()
+            .flatten()
+            .collect::<Vec<&SchematicPart>>();
+        parts.sort_by_key(|part| reading_order(part.position()));
+        parts
+    }
+
+    /// Part numbers in reading order.
+    pub fn part_numbers(&self) -> Vec<&SchematicPart> {
+        let mut part_numbers = self.part_numbers
+            .iter()
+            .filter_map(|&slot| self.parts[slot].as_ref())
+            .collect::<Vec<&SchematicPart>>();
+        part_numbers.sort_by_key(|part| reading_order(part.position()));
+        part_numbers
+    }
+
+    /// Gears in reading order.
+    pub fn gears(&self) -> Vec<&Gear> {
+        let mut gears = self.gears.values().collect::<Vec<&Gear>>();
+        gears.sort_by_key(|gear| reading_order(&gear.position));
+        gears
+    }
+
+    /// The character of the cell at `x`, `y`, `.` for empty cells and cells past the end of their row.
+    pub fn get(&self, x: u32, y: u32) -> char {
+        match self.slot_at(x, y) {
+            None => '.',
+            Some(slot) => self.parts[slot].as_ref().map_or('.', |part| part.char_at(x))
+        }
+    }
+
+    /// Writes `char` in the cell at `x`, `y`, growing the schematic if it's outside, and returns what changed.
+    ///
+    /// ASCII digits are part of numbers, `.` is empty and anything else is a symbol. Fails without changing anything if
+    /// `char` is a line break or the edit makes a number too large.
+    pub fn set(&mut self, x: u32, y: u32, char: char) -> Result<SchematicDiff, ParseError> {
+        if char == '\n' || char == '\r' {
+            let text = char.escape_default().to_string();
+            let error = ParseError::new(Day3::DAY, &text, &text, "Cells can't hold line breaks!");
+            return Err(ParseError { column: x as usize + 1, ..error.on_line(y as usize) });
+        }
+
+        if self.get(x, y) == char {
+            return Ok(SchematicDiff::default());
+        }
+
+        // The part at the cell changes, and numbers next to it are joined to it if it becomes a digit.
+        let mut replaced = self.slot_at(x, y).into_iter().collect::<Vec<usize>>();
+        if char.is_ascii_digit() {
+            for neighbour_x in [x.checked_sub(1), x.checked_add(1)].into_iter().flatten() {
+                if let Some(slot) = self.slot_at(neighbour_x, y) {
+                    if matches!(self.parts[slot], Some(SchematicPart::Number { .. })) && !replaced.contains(&slot) {
+                        replaced.push(slot);
+                    }
+                }
+            }
+        }
+
+        let (first_x, last_x) = replaced
+            .iter()
+            .filter_map(|&slot| self.parts[slot].as_ref())
+            .map(|part| (part.position().0, part.position().0 + part.width() - 1))
+            .fold((x, x), |(first_x, last_x), (start, end)| (first_x.min(start), last_x.max(end)));
+        let segment = (first_x..=last_x)
+            .map(|segment_x| if segment_x == x { char } else { self.get(segment_x, y) })
+            .collect::<String>();
+        let added = extract_segment(&segment, first_x, y)?;
+
+        let region = Region {
+            left: first_x.saturating_sub(1),
+            right: last_x.saturating_add(1),
+            up: y.saturating_sub(1),
+            down: y.saturating_add(1)
+        };
+        let (part_numbers_before, gears_before) = self.snapshot(region);
+        let (part_number_sum_before, gear_value_sum_before) = (self.part_number_sum, self.gear_value_sum);
+        let overflowing_gears_before = self.overflowing_gears;
+
+        for slot in self.slots_in(region) {
+            self.unclassify(slot);
+        }
+        let mut removed_parts = Vec::<SchematicPart>::new();
+        for slot in replaced {
+            removed_parts.extend(self.remove(slot));
+        }
+        removed_parts.sort_by_key(|part| reading_order(part.position()));
+        self.grow(last_x, y);
+        for part in &added {
+            self.insert(part.clone());
+        }
+        for slot in self.slots_in(region) {
+            self.classify(slot);
+        }
+
+        let (part_numbers_after, gears_after) = self.snapshot(region);
+        let (removed_parts, added_parts) = difference(removed_parts, added);
+        let (removed_part_numbers, added_part_numbers) = difference(part_numbers_before, part_numbers_after);
+        let (removed_gears, added_gears) = difference(gears_before, gears_after);
+        Ok(SchematicDiff {
+            removed_parts,
+            added_parts,
+            removed_part_numbers,
+            added_part_numbers,
+            removed_gears,
+            added_gears,
+            part_number_sum_change: self.part_number_sum as i128 - part_number_sum_before as i128,
+            gear_value_sum_change: match overflowing_gears_before + self.overflowing_gears {
+                0 => Some(self.gear_value_sum as i128 - gear_value_sum_before as i128),
+                _ => None
+            }
+        })
+    }
+
+    fn slot_at(&self, x: u32, y: u32) -> Option<usize> {
+        match self.rows.get(y as usize).and_then(|row| row.get(x as usize)) {
+            None | Some(&NO_PART) => None,
+            Some(&slot) => Some(slot as usize)
+        }
+    }
+
+    /// Distinct slots of the parts covering any cell of `region`.
+    fn slots_in(&self, region: Region) -> Vec<usize> {
+        let mut slots = (region.up..=region.down)
+            .flat_map(|y| (region.left..=region.right).filter_map(move |x| self.slot_at(x, y)))
+            .collect::<Vec<usize>>();
+        slots.sort_unstable();
+        slots.dedup();
+        slots
+    }
+
+    /// Part numbers and gears covering any cell of `region`, in reading order.
+    fn snapshot(&self, region: Region) -> (Vec<SchematicPart>, Vec<Gear>) {
+        let mut part_numbers = Vec::<SchematicPart>::new();
+        let mut gears = Vec::<Gear>::new();
+        for slot in self.slots_in(region) {
+            if self.part_numbers.contains(&slot) {
+                part_numbers.extend(self.parts[slot].clone());
+            }
+            if let Some(gear) = self.gears.get(&slot) {
+                gears.push(gear.clone());
+            }
+        }
+
+        part_numbers.sort_by_key(|part| reading_order(part.position()));
+        gears.sort_by_key(|gear| reading_order(&gear.position));
+        (part_numbers, gears)
+    }
+
+    /// Numbers adjacent to the part in `slot`, in reading order.
+    fn adjacent_numbers(&self, slot: usize) -> Vec<GearNumber> {
+        let part = match &self.parts[slot] {
+            None => return Vec::<GearNumber>::new(),
+            Some(part) => part
+        };
+
+        let mut numbers = self.slots_in(Region::around(part))
+            .into_iter()
+            .filter_map(|other_slot| match &self.parts[other_slot] {
+                Some(SchematicPart::Number { value, position, .. }) if other_slot != slot => Some(GearNumber { value: *value, position: *position }),
+                _ => None
+            })
+            .collect::<Vec<GearNumber>>();
+        numbers.sort_by_key(|number| reading_order(&number.position));
+        numbers
+    }
+
+    /// Adds the part in `slot` to the part numbers or gears if it is one.
+    fn classify(&mut self, slot: usize) {
+        match &self.parts[slot] {
+            Some(SchematicPart::Number { value, .. }) => {
+                let value = *value;
+                let part = self.parts[slot].as_ref().unwrap();
+                let is_part_number = self.slots_in(Region::around(part))
+                    .into_iter()
+                    .any(|other_slot| matches!(self.parts[other_slot], Some(SchematicPart::Symbol { .. })));
+                if is_part_number && self.part_numbers.insert(slot) {
+                    self.part_number_sum += value as u128;
+                }
+            }
+            Some(SchematicPart::Symbol { kind, position }) => {
+                if !self.rule.symbols.contains(kind) || self.gears.contains_key(&slot) {
+                    return;
+                }
+
+                let (kind, position) = (*kind, *position);
+                let numbers = self.adjacent_numbers(slot);
+                if self.rule.neighbours.allows(numbers.len()) {
+                    let gear = Gear { kind, position, numbers };
+                    match self.gear_value(&gear) {
+                        Some(value) => self.gear_value_sum += value,
+                        None => self.overflowing_gears += 1
+                    }
+                    self.gears.insert(slot, gear);
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Removes the part in `slot` from the part numbers and gears.
+    fn unclassify(&mut self, slot: usize) {
+        if self.part_numbers.remove(&slot) {
+            if let Some(SchematicPart::Number { value, .. }) = &self.parts[slot] {
+                self.part_number_sum -= *value as u128;
+            }
+        }
+        if let Some(gear) = self.gears.remove(&slot) {
+            match self.gear_value(&gear) {
+                Some(value) => self.gear_value_sum -= value,
+                None => self.overflowing_gears -= 1
+            }
+        }
+    }
+
+    /// Sum of the values of `gear`, `None` if one doesn't fit in a `u64`.
+    fn gear_value(&self, gear: &Gear) -> Option<u128> {
+        gear.combine(self.rule.combine).map(|values| values.iter().map(|&value| value as u128).sum())
+    }
+
+    fn remove(&mut self, slot: usize) -> Option<SchematicPart> {
+        let part = self.parts[slot].take()?;
+        let Coords(x, y) = *part.position();
+        self.rows[y as usize][x as usize..(x + part.width()) as usize].fill(NO_PART);
+        self.free_slots.push(slot);
+        Some(part)
+    }
+
+    fn insert(&mut self, part: SchematicPart) {
+        let Coords(x, y) = *part.position();
+        let width = part.width();
+        let slot = match self.free_slots.pop() {
+            Some(slot) => {
+                self.parts[slot] = Some(part);
+                slot
+            }
+            None => {
+                self.parts.push(Some(part));
+                self.parts.len() - 1
+            }
+        };
+        self.rows[y as usize][x as usize..(x + width) as usize].fill(slot as u32);
+    }
+
+    /// Makes sure the cell at `x`, `y` exists.
+    fn grow(&mut self, x: u32, y: u32) {
+        if self.rows.len() <= y as usize {
+            self.rows.resize(y as usize + 1, Vec::<u32>::new());
+        }
+
+        let row = &mut self.rows[y as usize];
+        if row.len() <= x as usize {
+            row.resize(x as usize + 1, NO_PART);
+        }
+    }
+}
+
+impl Region {
+    /// The cells adjacent to `part` and the part itself.
+    fn around(part: &SchematicPart) -> Self {
+        let Coords(x, y) = *part.position();
+        Region {
+            left: x.saturating_sub(1),
+            right: x.saturating_add(part.width()),
+            up: y.saturating_sub(1),
+            down: y.saturating_add(1)
+        }
+    }
+}
+
+/// No change.
+impl Default for SchematicDiff {
+    fn default() -> Self {
+        SchematicDiff {
+            removed_parts: vec![],
+            added_parts: vec![],
+            removed_part_numbers: vec![],
+            added_part_numbers: vec![],
+            removed_gears: vec![],
+            added_gears: vec![],
+            part_number_sum_change: 0,
+            gear_value_sum_change: Some(0)
+        }
+    }
+}
+
+impl SchematicDiff {
+    pub fn is_empty(&self) -> bool {
+        self == &SchematicDiff::default()
+    }
+}
+
+/// Writes the cells, `.` for empty ones, one row per line.
+impl Display for EditableSchematic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows.iter().enumerate() {
+            for x in 0..row.len() {
+                write!(f, "{}", self.get(x as u32, y as u32))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parts of `segment`, the cells of row `y` starting at column `first_x`.
+fn extract_segment(segment: &str, first_x: u32, y: u32) -> Result<Vec<SchematicPart>, ParseError> {
+    let schematic = SchematicPart::extract(segment)
+        .map_err(|error| ParseError { column: error.column + first_x as usize, ..error.on_line(y as usize) })?;
+
+    Ok(schematic
+        .parts()
+        .iter()
+        .map(|part| match part {
+            SchematicPart::Number { value, position, text } => SchematicPart::Number {
+                value: *value,
+                position: Coords(position.0 + first_x, y),
+                text: text.clone()
+            },
+            SchematicPart::Symbol { kind, position } => SchematicPart::Symbol { kind: *kind, position: Coords(position.0 + first_x, y) }
+        })
+        .collect::<Vec<SchematicPart>>())
+}
+
+/// Items only in `before` and items only in `after`.
+fn difference<T: Clone + PartialEq>(before: Vec<T>, after: Vec<T>) -> (Vec<T>, Vec<T>) {
+    let only_before = before
+        .iter()
+        .filter(|item| !after.contains(item))
+        .cloned()
+        .collect::<Vec<T>>();
+    let only_after = after
+        .into_iter()
+        .filter(|item| !before.contains(item))
+        .collect::<Vec<T>>();
+    (only_before, only_after)
+}
+
+fn reading_order(position: &Coords) -> (u32, u32) {
+    (position.1, position.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::Rng;
+    use crate::gear_ratios::{Combine, NeighbourCount};
+    use super::*;
+
+    const SCHEMATIC: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    fn number(text: &str, x: u32, y: u32) -> SchematicPart {
+        SchematicPart::Number { value: text.parse::<u64>().unwrap(), position: Coords(x, y), text: String::from(text) }
+    }
+
+    fn gear_number(value: u64, x: u32, y: u32) -> GearNumber {
+        GearNumber { value, position: Coords(x, y) }
+    }
+
+    fn editable(schematic: &str) -> EditableSchematic {
+        EditableSchematic::new(&SchematicPart::extract(schematic).unwrap(), GearRule::default())
+    }
+
+    /// Checks `editable` against a schematic extracted from scratch.
+    fn assert_matches_extracted(editable: &EditableSchematic) {
+        let text = editable.to_string();
+        let schematic = SchematicPart::extract(&text).unwrap();
+        assert_eq!(editable.parts(), schematic.parts().iter().collect::<Vec<&SchematicPart>>(), "{text}");
+        let gear_value_sum = schematic
+            .gear_values(editable.rule())
+            .and_then(|values| values.iter().try_fold(0u64, |sum, &value| sum.checked_add(value)));
+        assert_eq!(editable.part_number_sum(), Day3::part1(&schematic), "{text}");
+        assert_eq!(editable.gear_value_sum(), gear_value_sum, "{text}");
+        assert_eq!(editable.gears(), schematic.gears(editable.rule()).iter().collect::<Vec<&Gear>>(), "{text}");
+
+        let part_numbers = schematic
+            .parts()
+            .iter()
+            .filter(|part| schematic.is_part_number(part))
+            .collect::<Vec<&SchematicPart>>();
+        assert_eq!(editable.part_numbers(), part_numbers, "{text}");
+    }
+
+    #[test]
+    fn test_new() {
+        let editable = editable(SCHEMATIC);
+        assert_eq!((editable.part_number_sum(), editable.gear_value_sum()), (Some(4361), Some(467_835)));
+        assert_eq!(editable.to_string(), format!("{SCHEMATIC}\n"));
+        assert_eq!((editable.get(3, 1), editable.get(1, 0), editable.get(0, 1), editable.get(99, 99)), ('*', '6', '.', '.'));
+        assert_matches_extracted(&editable);
+    }
+
+    #[test]
+    fn test_remove_gear() {
+        let mut editable = editable(SCHEMATIC);
+        let gear = Gear { kind: '*', position: Coords(3, 1), numbers: vec![gear_number(467, 0, 0), gear_number(35, 2, 2)] };
+
+        let diff = editable.set(3, 1, '.').unwrap();
+        assert_eq!(diff, SchematicDiff {
+            removed_parts: vec![SchematicPart::Symbol { kind: '*', position: Coords(3, 1) }],
+            added_parts: vec![],
+            removed_part_numbers: vec![number("467", 0, 0), number("35", 2, 2)],
+            added_part_numbers: vec![],
+            removed_gears: vec![gear.clone()],
+            added_gears: vec![],
+            part_number_sum_change: -502,
+            gear_value_sum_change: Some(-16_345)
+        });
+        assert_eq!((editable.part_number_sum(), editable.gear_value_sum()), (Some(3859), Some(451_490)));
+        assert_matches_extracted(&editable);
+
+        let diff = editable.set(3, 1, '*').unwrap();
+        assert_eq!((diff.added_gears, diff.part_number_sum_change, diff.gear_value_sum_change), (vec![gear], 502, Some(16_345)));
+        assert_eq!((editable.part_number_sum(), editable.gear_value_sum()), (Some(4361), Some(467_835)));
+
+        assert!(editable.set(3, 1, '*').unwrap().is_empty());
+        assert!(editable.set(50, 50, '.').unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_change_digit() {
+        let mut editable = editable(SCHEMATIC);
+        let diff = editable.set(0, 0, '5').unwrap();
+        assert_eq!(diff.removed_parts, vec![number("467", 0, 0)]);
+        assert_eq!(diff.added_parts, vec![number("567", 0, 0)]);
+        assert_eq!(diff.removed_part_numbers, vec![number("467", 0, 0)]);
+        assert_eq!(diff.added_part_numbers, vec![number("567", 0, 0)]);
+        assert_eq!(diff.added_gears[0].numbers, vec![gear_number(567, 0, 0), gear_number(35, 2, 2)]);
+        assert_eq!((diff.part_number_sum_change, diff.gear_value_sum_change), (100, Some(3500)));
+        assert_matches_extracted(&editable);
+    }
+
+    #[test]
+    fn test_join_and_split_numbers() {
+        let mut editable = editable("1.2\n.*.");
+        assert_eq!((editable.part_number_sum(), editable.gear_value_sum()), (Some(3), Some(2)));
+
+        let diff = editable.set(1, 0, '0').unwrap();
+        assert_eq!(diff.removed_parts, vec![number("1", 0, 0), number("2", 2, 0)]);
+        assert_eq!(diff.added_parts, vec![number("102", 0, 0)]);
+        assert_eq!((diff.part_number_sum_change, diff.gear_value_sum_change), (99, Some(-2)));
+        assert_eq!(editable.to_string(), "102\n.*.\n");
+
+        let diff = editable.set(1, 0, '#').unwrap();
+        assert_eq!(diff.removed_parts, vec![number("102", 0, 0)]);
+        assert_eq!(diff.added_parts, vec![number("1", 0, 0), SchematicPart::Symbol { kind: '#', position: Coords(1, 0) }, number("2", 2, 0)]);
+        assert_eq!(editable.gear_value_sum(), Some(2));
+        assert_matches_extracted(&editable);
+
+        let diff = editable.set(5, 2, '0').unwrap();
+        assert_eq!(diff.added_parts, vec![number("0", 5, 2)]);
+        assert!(diff.added_part_numbers.is_empty());
+        assert_eq!(editable.to_string(), "1#2\n.*.\n.....0\n");
+        assert_matches_extracted(&editable);
+    }
+
+    #[test]
+    fn test_number_too_large() {
+        let mut editable = editable("9999999999999999999.\n*");
+        let error = editable.set(19, 0, '9').unwrap_err();
+        assert_eq!((error.day, error.line, error.column), (3, 1, 1));
+        assert_eq!(editable.to_string(), "9999999999999999999.\n*\n");
+        assert_eq!(editable.part_number_sum(), Some(9_999_999_999_999_999_999));
+    }
+
+    #[test]
+    fn test_sums_too_large() {
+        let mut small = editable("999999999*999999999");
+        assert_eq!((small.part_number_sum(), small.gear_value_sum()), (Some(1_999_999_998), Some(999_999_998_000_000_001)));
+        let diff = small.set(9, 0, '.').unwrap();
+        assert_eq!((diff.part_number_sum_change, diff.gear_value_sum_change), (-1_999_999_998, Some(-999_999_998_000_000_001)));
+
+        let mut large = editable("9999999999*9999999999");
+        assert_eq!((large.part_number_sum(), large.gear_value_sum()), (Some(19_999_999_998), None));
+        assert_matches_extracted(&large);
+        let diff = large.set(0, 0, '.').unwrap();
+        assert_eq!((diff.part_number_sum_change, diff.gear_value_sum_change), (-9_000_000_000, None));
+        assert_eq!((large.part_number_sum(), large.gear_value_sum()), (Some(10_999_999_998), Some(9_999_999_989_000_000_001)));
+        assert_matches_extracted(&large);
+
+        let mut huge = editable("9999999999999999999*9999999999999999999");
+        assert_eq!((huge.part_number_sum(), huge.gear_value_sum()), (None, None));
+        assert_matches_extracted(&huge);
+        let diff = huge.set(19, 0, '.').unwrap();
+        assert_eq!((diff.part_number_sum_change, diff.gear_value_sum_change), (-19_999_999_999_999_999_998, None));
+        assert_eq!((huge.part_number_sum(), huge.gear_value_sum()), (Some(0), Some(0)));
+        assert_matches_extracted(&huge);
+    }
+
+    #[test]
+    fn test_random_edits() {
+        let rules = [
+            GearRule::default(),
+            GearRule::new(&['*', '#'], NeighbourCount::AtLeast(1), Combine::List),
+            GearRule::new(&['#'], NeighbourCount::AtMost(1), Combine::Sum)
+        ];
+        let mut rng = Rng::new(25);
+
+        for rule in rules {
+            for _ in 0..20 {
+                let schematic = crate::generator::gear_ratios(&mut rng, 8);
+                let mut editable = EditableSchematic::new(&SchematicPart::extract(&schematic).unwrap(), rule.clone());
+
+                for _ in 0..40 {
+                    let (part_number_sum, gear_value_sum) = (editable.part_number_sum(), editable.gear_value_sum());
+                    let (x, y) = (rng.between(0, 10) as u32, rng.between(0, 10) as u32);
+                    let char = *rng.choose(&['.', '.', '*', '#', '0', '1', '7', '\n']);
+                    if char == '\n' {
+                        let before = editable.to_string();
+                        let error = editable.set(x, y, char).unwrap_err();
+                        assert_eq!((error.line, error.column), (y as usize + 1, x as usize + 1));
+                        assert_eq!(editable.to_string(), before);
+                        continue;
+                    }
+                    let diff = editable.set(x, y, char).unwrap();
+
+                    assert_eq!(editable.get(x, y), char);
+                    let part_number_sum = part_number_sum.unwrap() as i128 + diff.part_number_sum_change;
+                    let gear_value_sum = gear_value_sum.unwrap() as i128 + diff.gear_value_sum_change.unwrap();
+                    assert_eq!(editable.part_number_sum().unwrap() as i128, part_number_sum);
+                    assert_eq!(editable.gear_value_sum().unwrap() as i128, gear_value_sum);
+                    assert_matches_extracted(&editable);
+                }
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,374 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use crate::gear_ratios::{Coords, GearRule, Schematic, SchematicPart};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderFormat {
+    /// Text coloured with ANSI escape codes, for terminals.
+    Ansi,
+    /// A standalone HTML page.
+    Html
+}
+
+/// What a cell of the schematic shows, and so how it's coloured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellKind {
+    Empty,
+    PartNumber,
+    /// A number that isn't adjacent to any symbol.
+    OtherNumber,
+    Gear,
+    /// A symbol that isn't a gear.
+    Symbol
+}
+
+/// How often a symbol appears and how many of its appearances are gears.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolStatistics {
+    pub kind: char,
+    pub count: usize,
+    pub gears: usize,
+    /// Numbers adjacent to any of its appearances, a number next to two of them counts twice.
+    pub adjacent_numbers: usize
+}
+
+/// Renders a schematic with part numbers, other numbers and gears coloured differently, to check answers by eye.
+pub struct SchematicRenderer<'a> {
+    schematic: &'a Schematic,
+    rule: GearRule,
+    legend: bool,
+    statistics: bool
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+const HTML_STYLE: &str = "pre { line-height: 1.2; }
+.part-number { color: #2e7d32; font-weight: bold; }
+.other-number { color: #c62828; }
+.gear { color: #000; background: #fdd835; font-weight: bold; }
+.symbol { color: #1565c0; font-weight: bold; }
+.statistics td, .statistics th { padding: 0 0.5em; text-align: right; }";
+
+impl CellKind {
+    fn ansi(&self) -> Option<&'static str> {
+        match self {
+            CellKind::Empty => None,
+            CellKind::PartNumber => Some("\x1b[1;32m"),
+            CellKind::OtherNumber => Some("\x1b[31m"),
+            CellKind::Gear => Some("\x1b[1;30;43m"),
+            CellKind::Symbol => Some("\x1b[1;34m")
+        }
+    }
+
+    fn class(&self) -> Option<&'static str> {
+        match self {
+            CellKind::Empty => None,
+            CellKind::PartNumber => Some("part-number"),
+            CellKind::OtherNumber => Some("other-number"),
+            CellKind::Gear => Some("gear"),
+            CellKind::Symbol => Some("symbol")
+        }
+    }
+
+    /// Example and description in the legend.
+    fn legend(&self) -> (&'static str, &'static str) {
+        match self {
+            CellKind::Empty => (".", "empty"),
+            CellKind::PartNumber => ("123", "part number"),
+            CellKind::OtherNumber => ("123", "not a part number"),
+            CellKind::Gear => ("*", "gear"),
+            CellKind::Symbol => ("#", "other symbol")
+        }
+    }
+}
+
+impl<'a> SchematicRenderer<'a> {
+    /// A renderer for the puzzle's gears, without legend or statistics.
+    pub fn new(schematic: &'a Schematic) -> Self {
+        SchematicRenderer { schematic, rule: GearRule::default(), legend: false, statistics: false }
+    }
+
+    pub fn with_rule(self, rule: GearRule) -> Self {
+        SchematicRenderer { rule, ..self }
+    }
+
+    pub fn with_legend(self) -> Self {
+        SchematicRenderer { legend: true, ..self }
+    }
+
+    pub fn with_statistics(self) -> Self {
+        SchematicRenderer { statistics: true, ..self }
+    }
+
+    /// Kind of each part, in the order of [Schematic::parts].
+    pub fn part_kinds(&self) -> Vec<CellKind> {
+        let gears = self.gear_positions();
+        self.schematic
+            .parts()
+            .iter()
+            .map(|part| match part {
+                SchematicPart::Number { .. } if self.schematic.is_part_number(part) => CellKind::PartNumber,
+                SchematicPart::Number { .. } => CellKind::OtherNumber,
+                SchematicPart::Symbol { position, .. } if gears.contains(position) => CellKind::Gear,
+                SchematicPart::Symbol { .. } => CellKind::Symbol
+            })
+            .collect::<Vec<CellKind>>()
+    }
+
+    /// Statistics of each symbol, ordered by symbol.
+    pub fn statistics(&self) -> Vec<SymbolStatistics> {
+        let gears = self.gear_positions();
+        let mut statistics = BTreeMap::<char, SymbolStatistics>::new();
+
+        for part in self.schematic.parts() {
+            if let SchematicPart::Symbol { kind, position } = part {
+                let symbol_statistics = statistics
+                    .entry(*kind)
+                    .or_insert(SymbolStatistics { kind: *kind, count: 0, gears: 0, adjacent_numbers: 0 });
+                symbol_statistics.count += 1;
+                symbol_statistics.adjacent_numbers += self.schematic.adjacent_numbers(position).len();
+                if gears.contains(position) {
+                    symbol_statistics.gears += 1;
+                }
+            }
+        }
+
+        statistics.into_values().collect::<Vec<SymbolStatistics>>()
+    }
+
+    pub fn render(&self, format: RenderFormat) -> String {
+        match format {
+            RenderFormat::Ansi => self.render_ansi(),
+            RenderFormat::Html => self.render_html()
+        }
+    }
+
+    fn render_ansi(&self) -> String {
+        let mut rendered = String::new();
+        for row in self.rows() {
+            for (kind, text) in row {
+                match kind.ansi() {
+                    None => rendered.push_str(&text),
+                    Some(colour) => write!(rendered, "{colour}{text}{ANSI_RESET}").unwrap()
+                }
+            }
+            rendered.push('\n');
+        }
+
+        if self.legend {
+            let legend = [CellKind::PartNumber, CellKind::OtherNumber, CellKind::Gear, CellKind::Symbol]
+                .iter()
+                .map(|kind| {
+                    let (example, description) = kind.legend();
+                    format!("{}{example}{ANSI_RESET} {description}", kind.ansi().unwrap_or_default())
+                })
+                .collect::<Vec<String>>();
+            write!(rendered, "\nLegend: {}\n", legend.join(", ")).unwrap();
+        }
+
+        if self.statistics {
+            writeln!(rendered, "\nSymbol  Count  Gears  Adjacent numbers").unwrap();
+            for statistics in self.statistics() {
+                writeln!(rendered, "{:<6}  {:>5}  {:>5}  {:>16}",
+                         statistics.kind, statistics.count, statistics.gears, statistics.adjacent_numbers).unwrap();
+            }
+        }
+
+        rendered
+    }
+
+    fn render_html(&self) -> String {
+        let mut rendered = String::new();
+        writeln!(rendered, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Engine schematic</title>").unwrap();
+        writeln!(rendered, "<style>\n{HTML_STYLE}\n</style>\n</head>\n<body>").unwrap();
+
+        rendered.push_str("<pre class=\"schematic\">");
+        for row in self.rows() {
+            for (kind, text) in row {
+                match kind.class() {
+                    None => rendered.push_str(&escape_html(&text)),
+                    Some(class) => write!(rendered, "<span class=\"{class}\">{}</span>", escape_html(&text)).unwrap()
+                }
+            }
+            rendered.push('\n');
+        }
+        rendered.push_str("</pre>\n");
+
+        if self.legend {
+            rendered.push_str("<ul class=\"legend\">\n");
+            for kind in [CellKind::PartNumber, CellKind::OtherNumber, CellKind::Gear, CellKind::Symbol] {
+                let (example, description) = kind.legend();
+                writeln!(rendered, "<li><span class=\"{}\">{example}</span> {description}</li>", kind.class().unwrap_or_default()).unwrap();
+            }
+            rendered.push_str("</ul>\n");
+        }
+
+        if self.statistics {
+            rendered.push_str("<table class=\"statistics\">\n<tr><th>Symbol</th><th>Count</th><th>Gears</th><th>Adjacent numbers</th></tr>\n");
+            for statistics in self.statistics() {
+                writeln!(rendered, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                         escape_html(&statistics.kind.to_string()), statistics.count, statistics.gears, statistics.adjacent_numbers).unwrap();
+            }
+            rendered.push_str("</table>\n");
+        }
+
+        rendered.push_str("</body>\n</html>\n");
+        rendered
+    }
+
+    /// Rows of the schematic, each split into runs of cells of the same kind.
+    fn rows(&self) -> Vec<Vec<(CellKind, String)>> {
+        let kinds = self.part_kinds();
+        let parts = self.schematic.parts();
+
+        (0..self.schematic.rows())
+            .map(|y| {
+                let mut runs = Vec::<(CellKind, String)>::new();
+                for x in 0..self.schematic.row_width(y) {
+                    let (kind, char) = match self.schematic.part_index_at(x as u32, y as u32) {
+                        None => (CellKind::Empty, '.'),
+                        Some(index) => (kinds[index], parts[index].char_at(x as u32))
+                    };
+                    match runs.last_mut() {
+                        Some((run_kind, text)) if *run_kind == kind => text.push(char),
+                        _ => runs.push((kind, String::from(char)))
+                    }
+                }
+                runs
+            })
+            .collect::<Vec<Vec<(CellKind, String)>>>()
+    }
+
+    fn gear_positions(&self) -> HashSet<Coords> {
+        self.schematic
+            .gears(&self.rule)
+            .iter()
+            .map(|gear| gear.position)
+            .collect::<HashSet<Coords>>()
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gear_ratios::{Combine, NeighbourCount};
+    use super::*;
+
+    const SCHEMATIC: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    fn strip_ansi(text: &str) -> String {
+        let mut stripped = String::new();
+        let mut in_escape = false;
+        for char in text.chars() {
+            match char {
+                '\x1b' => in_escape = true,
+                'm' if in_escape => in_escape = false,
+                _ if in_escape => {}
+                _ => stripped.push(char)
+            }
+        }
+        stripped
+    }
+
+    #[test]
+    fn test_part_kinds() {
+        let schematic = SchematicPart::extract(SCHEMATIC).unwrap();
+        let kinds = SchematicRenderer::new(&schematic).part_kinds();
+        let kinds = schematic
+            .parts()
+            .iter()
+            .zip(kinds)
+            .map(|(part, kind)| match part {
+                SchematicPart::Number { text, .. } => (text.clone(), kind),
+                SchematicPart::Symbol { kind: symbol, .. } => (symbol.to_string(), kind)
+            })
+            .collect::<Vec<(String, CellKind)>>();
+        assert_eq!(kinds[..5], [
+            (String::from("467"), CellKind::PartNumber),
+            (String::from("114"), CellKind::OtherNumber),
+            (String::from("*"), CellKind::Gear),
+            (String::from("35"), CellKind::PartNumber),
+            (String::from("633"), CellKind::PartNumber)
+        ]);
+        assert_eq!(kinds[7], (String::from("*"), CellKind::Symbol));
+        assert_eq!(kinds[9], (String::from("58"), CellKind::OtherNumber));
+
+        let rule = GearRule::new(&['*'], NeighbourCount::AtLeast(1), Combine::Sum);
+        let kinds = SchematicRenderer::new(&schematic).with_rule(rule).part_kinds();
+        assert_eq!(kinds[7], CellKind::Gear);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let schematic = SchematicPart::extract(SCHEMATIC).unwrap();
+        let statistics = SchematicRenderer::new(&schematic).statistics();
+        assert_eq!(statistics, vec![
+            SymbolStatistics { kind: '#', count: 1, gears: 0, adjacent_numbers: 1 },
+            SymbolStatistics { kind: '$', count: 1, gears: 0, adjacent_numbers: 1 },
+            SymbolStatistics { kind: '*', count: 3, gears: 2, adjacent_numbers: 5 },
+            SymbolStatistics { kind: '+', count: 1, gears: 0, adjacent_numbers: 1 }
+        ]);
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let schematic = SchematicPart::extract("467..114..\n...*......\n..35......").unwrap();
+        let rendered = SchematicRenderer::new(&schematic).render(RenderFormat::Ansi);
+        assert_eq!(rendered, "\x1b[1;32m467\x1b[0m..\x1b[31m114\x1b[0m..
+...\x1b[1;30;43m*\x1b[0m......
+..\x1b[1;32m35\x1b[0m......
+");
+
+        let schematic = SchematicPart::extract(SCHEMATIC).unwrap();
+        let rendered = SchematicRenderer::new(&schematic).with_legend().with_statistics().render(RenderFormat::Ansi);
+        let rendered = strip_ansi(&rendered);
+        assert!(rendered.starts_with(&format!("{SCHEMATIC}\n\nLegend: 123 part number, 123 not a part number, * gear, # other symbol\n")));
+        assert!(rendered.ends_with("
+Symbol  Count  Gears  Adjacent numbers
+#           1      0                 1
+$           1      0                 1
+*           3      2                 5
++           1      0                 1
+"));
+    }
+
+    #[test]
+    fn test_render_keeps_cells() {
+        let schematic = SchematicPart::extract("0.007*...\n\n..<&>.").unwrap();
+        let rendered = SchematicRenderer::new(&schematic).render(RenderFormat::Ansi);
+        assert_eq!(strip_ansi(&rendered), "0.007*...\n\n..<&>.\n");
+
+        let schematic = Schematic::new(vec![SchematicPart::Symbol { kind: '#', position: Coords(2, 1) }]);
+        let rendered = SchematicRenderer::new(&schematic).render(RenderFormat::Ansi);
+        assert_eq!(strip_ansi(&rendered), "\n..#\n");
+    }
+
+    #[test]
+    fn test_render_html() {
+        let schematic = SchematicPart::extract("12<.\n....").unwrap();
+        let rendered = SchematicRenderer::new(&schematic).with_legend().with_statistics().render(RenderFormat::Html);
+        assert!(rendered.starts_with("<!DOCTYPE html>\n"));
+        assert!(rendered.contains("<pre class=\"schematic\"><span class=\"part-number\">12</span><span class=\"symbol\">&lt;</span>.\n....\n</pre>\n"));
+        assert!(rendered.contains("<li><span class=\"gear\">*</span> gear</li>"));
+        assert!(rendered.contains("<tr><td>&lt;</td><td>1</td><td>0</td><td>1</td></tr>"));
+        assert!(rendered.ends_with("</html>\n"));
+
+        let rendered = SchematicRenderer::new(&schematic).render(RenderFormat::Html);
+        assert!(!rendered.contains("legend") && !rendered.contains("<table"));
+    }
+}
\ No newline at end of file
@@ -1,4 +1,6 @@
-use std::str::Split;
+use crate::parse_error::{parse_numbers, ParseError};
+use crate::solution::Solution;
+use crate::warnings;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Scratchcard {
@@ -8,37 +10,39 @@ pub struct Scratchcard {
 }
 
 impl Scratchcard {
-    pub fn from_str(str: &str) -> Vec<Scratchcard> {
+    pub fn from_str(str: &str) -> Result<Vec<Scratchcard>, ParseError> {
         str
             .lines()
-            .filter_map(Scratchcard::from_line)
-            .collect::<Vec<Scratchcard>>()
+            .enumerate()
+            .map(|(index, line)| Scratchcard::from_line(line).map_err(|error| error.on_line(index)))
+            .collect::<Result<Vec<Scratchcard>, ParseError>>()
     }
 
-    fn from_line(line: &str) -> Option<Scratchcard> {
-        if line.len() < 48 {
-            return None;
-        }
-
-        let semicolon_index = line.find(':')?;
-        let game_id = line[5..semicolon_index]
-            .trim()
+    fn from_line(line: &str) -> Result<Scratchcard, ParseError> {
+        let semicolon_index = match line.find(':') {
+            Some(semicolon_index) if line.starts_with("Card ") => semicolon_index,
+            _ => return Err(ParseError::new(Day4::DAY, line, line, "Scratchcard line must start with 'Card <number>:'!"))
+        };
+        let game_id_str = line[5..semicolon_index].trim();
+        let game_id = game_id_str
             .parse::<u32>()
-            .ok()?;
-        let mut numbers = line[semicolon_index..]
-            .trim()
-            .split('|');
-        let winning_numbers = extract_numbers(&mut numbers)?;
-        let pulled_numbers = extract_numbers(&mut numbers)?;
-
-        Some(Scratchcard {
+            .map_err(|error| ParseError::new(Day4::DAY, line, game_id_str, format!("Unable to parse card number! {error}")))?;
+        let numbers = &line[semicolon_index + 1..];
+        let (winning_numbers, pulled_numbers) = match numbers.split_once('|') {
+            Some(numbers) => numbers,
+            None => return Err(ParseError::new(Day4::DAY, line, numbers, "Winning and pulled numbers must be separated by '|'!"))
+        };
+        let winning_numbers = parse_numbers(Day4::DAY, line, winning_numbers)?;
+        let pulled_numbers = parse_numbers(Day4::DAY, line, pulled_numbers)?;
+
+        Ok(Scratchcard {
             number: game_id,
             winning_numbers,
             pulled_numbers
         })
     }
 
-    fn match_count(self: &Self) -> u32 {
+    fn match_count(&self) -> u32 {
         let mut match_count = 0;
         for pulled_number in &self.pulled_numbers {
             for winning_number in &self.winning_numbers {
@@ -51,7 +55,7 @@ impl Scratchcard {
         match_count
     }
 
-    pub fn points(self: &Self) -> u32 {
+    pub fn points(&self) -> u32 {
         let mut match_count = 0;
         for pulled_number in &self.pulled_numbers {
             for winning_number in &self.winning_numbers {
@@ -69,21 +73,13 @@ impl Scratchcard {
     }
 }
 
-fn extract_numbers(numbers: &mut Split<char>) -> Option<Vec<u32>> {
-    Some(numbers
-        .next()?
-        .split(' ')
-        .filter_map(|number| number.trim().parse::<u32>().ok())
-        .collect::<Vec<u32>>())
-}
-
 #[derive(Debug, Eq, PartialEq)]
 pub struct CopyData {
     card_number: u32,
     copy_count: u32
 }
 
-pub fn get_initial_copies(scratchcards: &Vec<Scratchcard>) -> Vec<CopyData> {
+pub fn get_initial_copies(scratchcards: &[Scratchcard]) -> Vec<CopyData> {
     let mut copy_data = Vec::<CopyData>::new();
 
     for (index, scratchcard) in scratchcards.iter().enumerate() {
@@ -93,7 +89,7 @@ pub fn get_initial_copies(scratchcards: &Vec<Scratchcard>) -> Vec<CopyData> {
             for copy_index in 1..=match_count {
                 let copy_index = index + copy_index;
                 match scratchcards.get(copy_index) {
-                    None => eprintln!("Warning: copy index {copy_index} yielded no scratchcard!"),
+                    None => warnings::warn(format!("Copy index {copy_index} yielded no scratchcard!")),
                     Some(copy) => copy_data.push(CopyData {
                         card_number: copy.number,
                         copy_count: copy.match_count()
@@ -106,7 +102,7 @@ pub fn get_initial_copies(scratchcards: &Vec<Scratchcard>) -> Vec<CopyData> {
     copy_data
 }
 
-pub fn get_copy_data(scratchcards: &Vec<Scratchcard>, copy_data: &Vec<CopyData>) -> Vec<CopyData> {
+pub fn get_copy_data(scratchcards: &[Scratchcard], copy_data: &[CopyData]) -> Vec<CopyData> {
     let mut new_copy_data = Vec::<CopyData>::new();
 
     for copy_data in copy_data {
@@ -127,6 +123,41 @@ pub fn get_copy_data(scratchcards: &Vec<Scratchcard>, copy_data: &Vec<CopyData>)
     new_copy_data
 }
 
+pub fn count_total_scratchcards(scratchcards: &[Scratchcard]) -> usize {
+    let mut total_scratchcards_count = scratchcards.len();
+    let mut copy_data = get_initial_copies(scratchcards);
+
+    while !copy_data.is_empty() {
+        total_scratchcards_count += copy_data.len();
+        copy_data = get_copy_data(scratchcards, &copy_data);
+    }
+
+    total_scratchcards_count
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Scratchcards";
+
+    type Input<'a> = Vec<Scratchcard>;
+    type Part1 = u32;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Scratchcard::from_str(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        Some(input.iter().map(Scratchcard::points).sum())
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        Some(count_total_scratchcards(input))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -134,11 +165,17 @@ mod tests {
     #[test]
     fn test_from_line() {
         let scratchcard = Scratchcard::from_line("Card 1: 34 67  3 99 23 | 34 85 33 31 22 53 33 24");
-        assert_eq!(scratchcard, Some(Scratchcard {
+        assert_eq!(scratchcard, Ok(Scratchcard {
             number: 1,
             winning_numbers: vec![34, 67, 3, 99, 23],
             pulled_numbers: vec![34, 85, 33, 31, 22, 53, 33, 24]
-        }))
+        }));
+
+        let scratchcard = Scratchcard::from_line("Card 1: 34 67  3 99 23 34 85 33 31 22 53 33 24");
+        assert_eq!(scratchcard.map_err(|error| error.column), Err(8));
+
+        let scratchcard = Scratchcard::from_line("Card 1: 34 67  3 99 23 | 34 85 3x 31 22 53 33 24");
+        assert_eq!(scratchcard.map_err(|error| (error.column, error.text)), Err((32, String::from("3x"))));
     }
 
     #[test]
@@ -163,7 +200,11 @@ Card 3:  8 41 25 14 99 | 45 23 52 74 35 24 51 63
             winning_numbers: vec![8, 41, 25, 14, 99],
             pulled_numbers: vec![45, 23, 52, 74, 35, 24, 51, 63]
         };
-        assert_eq!(scratchcards, vec![scratchcard1, scratchcard2, scratchcard3]);
+        assert_eq!(scratchcards, Ok(vec![scratchcard1, scratchcard2, scratchcard3]));
+
+        let scratchcards = Scratchcard::from_str("Card 1: 34 67  3 99 23 | 34 85 33 31 22 53 33 24
+Cart 2: 45 73 23 14 74 | 33 25 34 31 22 52 11  3");
+        assert_eq!(scratchcards.map_err(|error| (error.line, error.column)), Err((2, 1)));
     }
 
     #[test]
@@ -175,7 +216,7 @@ Card 3:  8 41 25 14 99 | 45 23 52 74 35 24 51 63
             winning_numbers: vec![34, 67, 3, 99, 23],
             pulled_numbers: vec![34, 85, 33, 31, 22, 53, 33, 24]
         };
-        assert_eq!(scratchcards, Some(scratchcard1));
+        assert_eq!(scratchcards, Ok(scratchcard1));
     }
 
     #[test]
@@ -209,6 +250,7 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11")
+            .unwrap()
             .iter()
             .map(|scratchcard| scratchcard.points())
             .sum::<u32>();
@@ -222,7 +264,7 @@ Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11");
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11").unwrap();
         let copy_data = get_initial_copies(&scratchcards);
         assert_eq!(copy_data, vec![
             CopyData { card_number: 2, copy_count: 2 },
@@ -1,4 +1,6 @@
 use rayon::prelude::*;
+use crate::parse_error::{parse_numbers, ParseError};
+use crate::solution::Solution;
 
 #[derive(Debug, PartialEq)]
 pub struct Almanac {
@@ -7,39 +9,33 @@ pub struct Almanac {
 }
 
 impl Almanac {
-    pub fn new(str: &str) -> Option<Self> {
-        let mut lines = str.lines();
-        let seeds = extract_seeds(lines.next()?);
-
-        let mut maps = Vec::<Map>::new();
-        let mut current_map = String::new();
-        for line in lines {
-            if line.is_empty() {
-                match Map::new(&current_map) {
-                    None => println!("\"{current_map}\" produced invalid map!"),
-                    Some(map) => maps.push(map)
-                }
-                current_map = String::new();
-            } else {
-                current_map += line;
-                current_map += "\n";
-            }
-        }
-
-        if let Some(map) = Map::new(&current_map) {
-            maps.push(map);
-        }
-
-        Some(Self {
+    pub fn new(str: &str) -> Result<Self, ParseError> {
+        let mut blocks = extract_blocks(str).into_iter();
+        let seeds_block = blocks.next().unwrap_or(str);
+        let seeds = extract_seeds(seeds_block).map_err(|error| error.within(str, seeds_block))?;
+        let maps = blocks
+            .map(|block| Map::new(block).map_err(|error| error.within(str, block)))
+            .collect::<Result<Vec<Map>, ParseError>>()?;
+        log::debug!("Almanac has {} seed ranges and {} maps", seeds.len(), maps.len());
+
+        Ok(Self {
             seeds,
             maps
         })
     }
 
-    pub fn find_lowest_location(self: &Self) {
-        (&self.seeds).into_par_iter().for_each(|seed| {
+    pub fn find_lowest_seed_location(&self) -> Option<u64> {
+        self.seeds
+            .iter()
+            .flat_map(|seed| [seed.range_start, seed.length])
+            .filter_map(|seed| self.transform(seed, "seed", "location"))
+            .min()
+    }
+
+    pub fn find_lowest_location(&self) -> Option<u64> {
+        (&self.seeds).into_par_iter().filter_map(|seed| {
+            log::debug!("Running seeds {} -> {}", seed.range_start, seed.range_end());
             let mut min_location = Option::<u64>::None;
-            println!("\nRunning seed {} -> {}", seed.range_start, seed.range_end());
             for seed in seed.range_start..=seed.range_end() {
                 let location = self.transform(seed, "seed", "location");
                 match location {
@@ -47,18 +43,19 @@ impl Almanac {
                     Some(location) => match min_location {
                         None => min_location = Some(location),
                         Some(min_l) => if min_l > location {
+                            log::trace!("New min location: {location}");
                             min_location = Some(location);
-                            println!("New min location: {min_l}");
                         }
                     }
                 }
             }
 
-            println!("Lowest location for seed {} -> {} was {min_location:?}", seed.range_start, seed.range_end());
-        });
+            log::debug!("Lowest location for seeds {} -> {} is {min_location:?}", seed.range_start, seed.range_end());
+            min_location
+        }).min()
     }
 
-    fn transform(self: &Self, input: u64, input_name: &str, output_name: &str) -> Option<u64> {
+    fn transform(&self, input: u64, input_name: &str, output_name: &str) -> Option<u64> {
         let mut current_value = input;
         let mut current_name = &String::from(input_name);
         let output_name = &String::from(output_name);
@@ -78,53 +75,70 @@ impl Almanac {
 }
 
 #[derive(Debug, PartialEq)]
-struct Seed {
+pub struct Seed {
     range_start: u64,
     length: u64
 }
 
 impl Seed {
-    fn new(str: &str) -> Option<Self> {
-        let mut parts = str
-            .split(' ')
-            .filter_map(|part| part.parse::<u64>().ok())
-            .collect::<Vec<u64>>();
-        let range_start = *parts.get(0)?;
-        let length = *parts.get(1)?;
-        Some(Self {
-            range_start,
-            length
-        })
+    pub fn new(str: &str) -> Result<Self, ParseError> {
+        match parse_numbers::<u64>(Day5::DAY, str, str)?.as_slice() {
+            &[range_start, length] => Ok(Self {
+                range_start,
+                length
+            }),
+            _ => Err(ParseError::new(Day5::DAY, str, str, "Seed must be '<range start> <length>'!"))
+        }
     }
 
-    fn range_end(self: &Self) -> u64 {
+    fn range_end(&self) -> u64 {
         self.range_start + self.length
     }
 }
 
-fn extract_seeds(line: &str) -> Vec<Seed> {
-    let mut parts = line[7..]
-        .split(' ')
-        .filter_map(|part| part.parse::<u64>().ok());
-    let mut range_start = 0;
-    let mut length = 0;
-    let mut next_part_is_length = false;
-    let mut seeds = Vec::<Seed>::new();
-
-    for part in parts {
-        if next_part_is_length {
-            length = part;
-            seeds.push(Seed {
-                range_start,
-                length
-            })
-        } else {
-            range_start = part;
+fn extract_seeds(line: &str) -> Result<Vec<Seed>, ParseError> {
+    let numbers = match line.strip_prefix("seeds:") {
+        None => return Err(ParseError::new(Day5::DAY, line, line, "Almanac must start with 'seeds:'!")),
+        Some(numbers) => numbers
+    };
+    let parts = parse_numbers::<u64>(Day5::DAY, line, numbers)?;
+
+    if parts.len() % 2 != 0 {
+        return Err(ParseError::new(Day5::DAY, line, numbers, "Seeds must be pairs of '<range start> <length>'!"));
+    }
+
+    Ok(parts
+        .chunks(2)
+        .map(|pair| Seed {
+            range_start: pair[0],
+            length: pair[1]
+        })
+        .collect::<Vec<Seed>>())
+}
+
+/// Splits `str` into blocks of lines separated by empty lines.
+fn extract_blocks(str: &str) -> Vec<&str> {
+    let mut blocks = Vec::<&str>::new();
+    let mut block_start = Option::<usize>::None;
+    let mut offset = 0;
+
+    for line in str.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(start) = block_start.take() {
+                blocks.push(str[start..offset].trim_end());
+            }
+        } else if block_start.is_none() {
+            block_start = Some(offset);
         }
-        next_part_is_length = !next_part_is_length;
+
+        offset += line.len();
     }
 
-    seeds
+    if let Some(start) = block_start {
+        blocks.push(str[start..].trim_end());
+    }
+
+    blocks
 }
 
 #[derive(Debug, PartialEq)]
@@ -135,24 +149,24 @@ struct Map {
 }
 
 impl Map {
-    fn new(str: &str) -> Option<Self> {
+    fn new(str: &str) -> Result<Self, ParseError> {
         let mut lines = str.lines();
-        let title = lines.next()?;
-        let space_index = title.find(' ')?;
-        let mut title_parts = title[..space_index].split('-');
-        let source_name = title_parts.next()?;
-        let destination_name = title_parts.last()?;
+        let title = lines.next().unwrap_or(str);
+        let (source_name, destination_name) = title
+            .strip_suffix(" map:")
+            .and_then(|names| names.split_once("-to-"))
+            .ok_or_else(|| ParseError::new(Day5::DAY, str, title, "Map must start with '<source>-to-<destination> map:'!"))?;
         let range_transformations = lines
-            .filter_map(RangeTransformation::new)
-            .collect::<Vec<RangeTransformation>>();
-        Some(Self {
+            .map(|line| RangeTransformation::new(line).map_err(|error| error.within(str, line)))
+            .collect::<Result<Vec<RangeTransformation>, ParseError>>()?;
+        Ok(Self {
             source_name: String::from(source_name),
             destination_name: String::from(destination_name),
             range_transformations
         })
     }
 
-    fn convert(self: &Self, input: u64) -> u64 {
+    fn convert(&self, input: u64) -> u64 {
         for range_transformer in &self.range_transformations {
             if input >= range_transformer.source_range_start
                 && input <= range_transformer.source_range_end() {
@@ -172,26 +186,45 @@ struct RangeTransformation {
 }
 
 impl RangeTransformation {
-    fn new(line: &str) -> Option<Self> {
-        let parts = line
-            .split(' ')
-            .filter_map(|part| part.parse::<u64>().ok())
-            .collect::<Vec<u64>>();
-        let destination_range_start = *parts.get(0)?;
-        let source_range_start = *parts.get(1)?;
-        let range_length = *parts.get(2)?;
-        Some(Self {
-            destination_range_start,
-            source_range_start,
-            range_length
-        })
+    fn new(line: &str) -> Result<Self, ParseError> {
+        match parse_numbers::<u64>(Day5::DAY, line, line)?.as_slice() {
+            &[destination_range_start, source_range_start, range_length] => Ok(Self {
+                destination_range_start,
+                source_range_start,
+                range_length
+            }),
+            _ => Err(ParseError::new(Day5::DAY, line, line, "Range must be '<destination start> <source start> <length>'!"))
+        }
     }
 
-    fn source_range_end(self: &Self) -> u64 {
+    fn source_range_end(&self) -> u64 {
         self.source_range_start + self.range_length
     }
 }
 
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+    type Input<'a> = Almanac;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Almanac::new(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        input.find_lowest_seed_location()
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        input.find_lowest_location()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,7 +232,7 @@ mod tests {
     #[test]
     fn test_range_transformation_new() {
         let range_transformation = RangeTransformation::new("52 86 4");
-        assert_eq!(range_transformation, Some(RangeTransformation {
+        assert_eq!(range_transformation, Ok(RangeTransformation {
             destination_range_start: 52,
             source_range_start: 86,
             range_length: 4
@@ -211,7 +244,7 @@ mod tests {
         let map = Map::new("seed-to-soil map:
 50 98 2
 52 50 48");
-        assert_eq!(map, Some(Map {
+        assert_eq!(map, Ok(Map {
             source_name: String::from("seed"),
             destination_name: String::from("soil"),
             range_transformations: vec![
@@ -256,7 +289,7 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4");
-        assert_eq!(almanac, Some(Almanac {
+        assert_eq!(almanac, Ok(Almanac {
             seeds: vec![
                 Seed { range_start: 79, length: 14},
                 Seed { range_start: 55, length: 13 }],
@@ -289,6 +322,32 @@ humidity-to-location map:
         }));
     }
 
+    #[test]
+    fn test_almanac_new_invalid() {
+        let almanac = Almanac::new("seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 x 2");
+        assert_eq!(almanac.map_err(|error| (error.line, error.column, error.text)), Err((9, 4, String::from("x"))));
+
+        let almanac = Almanac::new("seeds: 79 14 55
+
+seed-to-soil map:
+50 98 2");
+        assert_eq!(almanac.map_err(|error| (error.line, error.column)), Err((1, 7)));
+
+        let almanac = Almanac::new("seeds: 79 14 55 13
+
+seed to soil:
+50 98 2");
+        assert_eq!(almanac.map_err(|error| (error.line, error.column)), Err((3, 1)));
+    }
+
     #[test]
     fn test_convert() {
         let map = Map::new("seed-to-soil map:
@@ -354,7 +413,7 @@ humidity-to-location map:
     #[test]
     fn test_seed_new() {
         let seed = Seed::new("45 16");
-        assert_eq!(seed, Some(Seed {
+        assert_eq!(seed, Ok(Seed {
             range_start: 45,
             length: 16
         }))
@@ -363,9 +422,9 @@ humidity-to-location map:
     #[test]
     fn test_extract_seeds() {
         let seeds = extract_seeds("seeds: 79 14 55 13");
-        assert_eq!(seeds, vec![
+        assert_eq!(seeds, Ok(vec![
             Seed { range_start: 79, length: 14 },
             Seed { range_start: 55, length: 13 }
-        ])
+        ]))
     }
 }
\ No newline at end of file
@@ -1,27 +1,35 @@
-#[derive(Debug, PartialEq)]
+use crate::parse_error::{parse_numbers, ParseError};
+use crate::solution::Solution;
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct RaceRecord {
     time: u64,
     distance: u64
 }
 
 impl RaceRecord {
-    pub fn extract(str: &str) -> Vec<Self> {
+    pub fn extract(str: &str) -> Result<Vec<Self>, ParseError> {
         let mut lines = str.lines();
         let times = match lines.next() {
-            Some(line) => get_values(line),
-            None => return vec![]
+            Some(line) => get_values(line, "Time:")?,
+            None => return Err(ParseError::new(Day6::DAY, str, str, "Race records must start with a 'Time:' line!"))
         };
         let distances = match lines.next() {
-            Some(line) => get_values(line),
-            None => return vec![]
+            Some(line) => get_values(line, "Distance:").map_err(|error| error.on_line(1))?,
+            None => return Err(ParseError::new(Day6::DAY, str, &str[str.len()..], "Race records must have a 'Distance:' line!"))
         };
+
+        if times.len() != distances.len() {
+            return Err(ParseError::new(Day6::DAY, str, str, "Race records must have as many times as distances!"));
+        }
+
         let mut races = Vec::<Self>::new();
 
         for (&time, distance) in times.iter().zip(distances) {
             races.push(RaceRecord { time, distance })
         }
 
-        races
+        Ok(races)
     }
 
     pub fn transform(sub_values: Vec<Self>) -> Self {
@@ -40,15 +48,10 @@ impl RaceRecord {
     }
 }
 
-fn get_values(line: &str) -> Vec<u64> {
-    let colon_index = line.find(':');
-    match colon_index {
-        None => vec![],
-        Some(colon_index) => line[colon_index+1..]
-            .trim()
-            .split(' ')
-            .filter_map(|part| part.parse::<u64>().ok())
-            .collect::<Vec<u64>>()
+fn get_values(line: &str, label: &str) -> Result<Vec<u64>, ParseError> {
+    match line.strip_prefix(label) {
+        None => Err(ParseError::new(Day6::DAY, line, line, format!("Line must start with '{label}'!"))),
+        Some(values) => parse_numbers(Day6::DAY, line, values)
     }
 }
 
@@ -78,17 +81,45 @@ impl ToyBoat {
         }
     }
 
-    fn distance(self: &Self, hold_time: u64, total_time: u64) -> u64 {
+    fn distance(&self, hold_time: u64, total_time: u64) -> u64 {
         self.speed_rate * hold_time * (total_time - hold_time)
     }
 
-    pub fn count_winning_hold_times(self: &Self, race_record: &RaceRecord) -> u32 {
+    pub fn count_winning_hold_times(&self, race_record: &RaceRecord) -> u32 {
         (1..race_record.time)
             .filter(|&hold_time| self.distance(hold_time, race_record.time) > race_record.distance)
             .count() as u32
     }
 }
 
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Wait For It";
+
+    type Input<'a> = Vec<RaceRecord>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        RaceRecord::extract(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        let my_toy_boat = ToyBoat::get_my();
+        input
+            .iter()
+            .map(|race_record| my_toy_boat.count_winning_hold_times(race_record))
+            .reduce(|product, x| product * x)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        let actual_race_record = RaceRecord::transform(input.clone());
+        Some(ToyBoat::get_my().count_winning_hold_times(&actual_race_record))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,11 +128,18 @@ mod tests {
     fn test_extract() {
         let race_records = RaceRecord::extract("Time:      7  15   30
 Distance:  9  40  200");
-        assert_eq!(race_records, vec![
+        assert_eq!(race_records, Ok(vec![
             RaceRecord { time: 7, distance: 9 },
             RaceRecord { time: 15, distance: 40 },
             RaceRecord { time: 30, distance: 200 }
-        ])
+        ]));
+
+        let race_records = RaceRecord::extract("Time:      7  15   30
+Distance:  9  4O  200");
+        assert_eq!(race_records.map_err(|error| (error.line, error.column, error.text)), Err((2, 15, String::from("4O"))));
+
+        let race_records = RaceRecord::extract("Time:      7  15   30");
+        assert_eq!(race_records.map_err(|error| error.line), Err(1));
     }
 
     #[test]
@@ -121,8 +159,8 @@ Distance:  9  40  200");
     fn test_count_winning_hold_times() {
         let my_toy_boat = ToyBoat::get_my();
         let race_records = RaceRecord::extract("Time:      7  15   30
-Distance:  9  40  200");
-        let winning_hold_times_count = my_toy_boat.count_winning_hold_times(race_records.get(0).unwrap());
+Distance:  9  40  200").unwrap();
+        let winning_hold_times_count = my_toy_boat.count_winning_hold_times(race_records.first().unwrap());
         assert_eq!(winning_hold_times_count, 4);
         let winning_hold_times_count = my_toy_boat.count_winning_hold_times(race_records.get(1).unwrap());
         assert_eq!(winning_hold_times_count, 8);
@@ -141,7 +179,7 @@ Distance:  9  40  200");
     #[test]
     fn test_transform() {
         let race_records = RaceRecord::extract("Time:      7  15   30
-Distance:  9  40  200");
+Distance:  9  40  200").unwrap();
         assert_eq!(RaceRecord::transform(race_records), RaceRecord {
             time: 71530,
             distance: 940200
@@ -1,6 +1,8 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use crate::camel_cards::Strength::{FiveOfAKind, FourOfAKind, FullHouse, HighCard, Pair, ThreeOfAKind, TwoPairs};
+use crate::parse_error::ParseError;
+use crate::solution::Solution;
 
 #[derive(Debug)]
 pub struct Play {
@@ -9,24 +11,34 @@ pub struct Play {
 }
 
 impl Play {
-    pub fn extract(str: &str) -> Vec<Self> {
+    pub fn extract(str: &str) -> Result<Vec<Self>, ParseError> {
         str
             .lines()
-            .filter_map(Self::new)
-            .collect::<Vec<Self>>()
+            .enumerate()
+            .map(|(index, line)| Self::new(line).map_err(|error| error.on_line(index)))
+            .collect::<Result<Vec<Self>, ParseError>>()
     }
 
-    fn new(str: &str) -> Option<Self> {
-        let mut parts = str.split(' ');
-        let hand = Hand::new(parts.next()?);
-        let bid = parts.next()?.parse::<u32>().ok()?;
-        Some(Self { hand, bid })
+    fn new(str: &str) -> Result<Self, ParseError> {
+        let (hand, bid) = match str.split_once(' ') {
+            Some(parts) => parts,
+            None => return Err(ParseError::new(Day7::DAY, str, str, "Play must be '<hand> <bid>'!"))
+        };
+        let hand = Hand::new(hand).map_err(|error| error.within(str, hand))?;
+        let bid = bid
+            .parse::<u32>()
+            .map_err(|error| ParseError::new(Day7::DAY, str, bid, format!("Unable to parse bid! {error}")))?;
+        Ok(Self { hand, bid })
     }
 
     pub fn bid(&self) -> u32 {
         self.bid
     }
 
+    pub fn cmp_without_jokers(&self, other: &Self) -> Ordering {
+        self.hand.cmp_without_jokers(&other.hand)
+    }
+
     pub fn hand(&self) -> String {
         self.hand.cards.iter().map(|card| match card {
             Card::A => 'A',
@@ -93,15 +105,21 @@ pub struct Hand {
 }
 
 impl Hand {
-    fn new(str: &str) -> Self {
+    fn new(str: &str) -> Result<Self, ParseError> {
         let cards = str
-            .chars()
-            .filter_map(Card::new)
-            .collect::<Vec<Card>>();
-        Self { cards }
+            .char_indices()
+            .map(|(index, c)| Card::new(c).ok_or_else(|| ParseError::new(
+                Day7::DAY, str, &str[index..index + c.len_utf8()], "Card must be one of 'AKQJT98765432'!")))
+            .collect::<Result<Vec<Card>, ParseError>>()?;
+
+        if cards.len() != 5 {
+            return Err(ParseError::new(Day7::DAY, str, str, "Hand must have 5 cards!"));
+        }
+
+        Ok(Self { cards })
     }
 
-    fn strength(self: &Self) -> Option<Strength> {
+    fn strength(&self) -> Option<Strength> {
         if self.cards.len() != 5 {
             return None;
         }
@@ -281,6 +299,41 @@ impl Hand {
             (**fourth_card, **third_card, **second_card)
         }
     }
+
+    fn strength_without_jokers(&self) -> Option<Strength> {
+        if self.cards.len() != 5 {
+            return None;
+        }
+
+        let mut checked_cards = HashMap::<Card, u32>::new();
+
+        for card in &self.cards {
+            let card_count = checked_cards.entry(*card).or_insert(0);
+            *card_count += 1;
+        }
+
+        let mut counts = checked_cards.into_values().collect::<Vec<u32>>();
+        counts.sort_unstable_by(|left, right| right.cmp(left));
+
+        Some(match counts.as_slice() {
+            [5] => FiveOfAKind,
+            [4, 1] => FourOfAKind,
+            [3, 2] => FullHouse,
+            [3, 1, 1] => ThreeOfAKind,
+            [2, 2, 1] => TwoPairs,
+            [2, 1, 1, 1] => Pair,
+            _ => HighCard
+        })
+    }
+
+    fn cmp_without_jokers(&self, other: &Self) -> Ordering {
+        self.strength_without_jokers()
+            .cmp(&other.strength_without_jokers())
+            .then_with(|| self.cards
+                .iter()
+                .map(Card::value_without_jokers)
+                .cmp(other.cards.iter().map(Card::value_without_jokers)))
+    }
 }
 
 impl PartialEq<Self> for Hand {
@@ -322,7 +375,7 @@ impl Eq for Hand {
 
 impl Ord for Hand {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(&other).unwrap()
+        self.partial_cmp(other).unwrap()
     }
 }
 
@@ -421,7 +474,7 @@ impl Eq for Strength {
 
 impl Ord for Strength {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(&other).unwrap()
+        self.partial_cmp(other).unwrap()
     }
 }
 
@@ -438,10 +491,21 @@ impl Card {
             'Q' => Some(Card::Q),
             'J' => Some(Card::J),
             'T' => Some(Card::T),
-            '2'|'3'|'4'|'5'|'6'|'7'|'8'|'9' => c.to_digit(10).map(|digit| Card::Number(digit)),
+            '2'|'3'|'4'|'5'|'6'|'7'|'8'|'9' => c.to_digit(10).map(Card::Number),
             _ => None
         }
     }
+
+    fn value_without_jokers(&self) -> u32 {
+        match self {
+            Card::A => 14,
+            Card::K => 13,
+            Card::Q => 12,
+            Card::J => 11,
+            Card::T => 10,
+            Card::Number(n) => *n
+        }
+    }
 }
 
 impl PartialEq<Self> for Card {
@@ -521,11 +585,46 @@ impl Ord for Card {
     }
 }
 
+pub fn total_winnings(ranked_plays: &[&Play]) -> u64 {
+    ranked_plays
+        .iter()
+        .enumerate()
+        .map(|(index, play)| play.bid as u64 * (index as u64 + 1))
+        .sum::<u64>()
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Camel Cards";
+
+    type Input<'a> = Vec<Play>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Play::extract(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        let mut plays = input.iter().collect::<Vec<&Play>>();
+        plays.sort_by(|left, right| left.cmp_without_jokers(right));
+        Some(total_winnings(&plays))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        let mut plays = input.iter().collect::<Vec<&Play>>();
+        plays.sort();
+        Some(total_winnings(&plays))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::Ordering;
-    use crate::camel_cards::{Card, Hand, Play, Strength};
-    use crate::camel_cards::Strength::{FiveOfAKind, FourOfAKind, FullHouse, HighCard, Pair, ThreeOfAKind, TwoPairs};
+    use crate::camel_cards::{total_winnings, Card, Hand, Play, Strength};
+    use crate::camel_cards::Strength::{FiveOfAKind, FourOfAKind, FullHouse, Pair, ThreeOfAKind};
 
     #[test]
     fn test_new_card() {
@@ -572,7 +671,7 @@ mod tests {
 
     #[test]
     fn test_new_hand() {
-        let hand = Hand::new("32T3K");
+        let hand = Hand::new("32T3K").unwrap();
         assert_eq!(hand, Hand {
             cards: vec![
                 Card::Number(3),
@@ -583,7 +682,7 @@ mod tests {
             ]
         });
 
-        let hand = Hand::new("T55J5");
+        let hand = Hand::new("T55J5").unwrap();
         assert_eq!(hand, Hand {
             cards: vec![
                 Card::T,
@@ -594,7 +693,7 @@ mod tests {
             ]
         });
 
-        let hand = Hand::new("KK677");
+        let hand = Hand::new("KK677").unwrap();
         assert_eq!(hand, Hand {
             cards: vec![
                 Card::K,
@@ -605,7 +704,7 @@ mod tests {
             ]
         });
 
-        let hand = Hand::new("KTJJT");
+        let hand = Hand::new("KTJJT").unwrap();
         assert_eq!(hand, Hand {
             cards: vec![
                 Card::K,
@@ -616,7 +715,7 @@ mod tests {
             ]
         });
 
-        let hand = Hand::new("QQQJA");
+        let hand = Hand::new("QQQJA").unwrap();
         assert_eq!(hand, Hand {
             cards: vec![
                 Card::Q,
@@ -635,45 +734,56 @@ T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483");
-        assert_eq!(plays, vec![
+        assert_eq!(plays, Ok(vec![
             Play::new("32T3K 765").unwrap(),
             Play::new("T55J5 684").unwrap(),
             Play::new("KK677 28").unwrap(),
             Play::new("KTJJT 220").unwrap(),
             Play::new("QQQJA 483").unwrap()
-        ]);
+        ]));
+
+        let plays = Play::extract("32T3K 765
+T55J5 6x4");
+        assert_eq!(plays.map_err(|error| (error.line, error.column, error.text)), Err((2, 7, String::from("6x4"))));
+
+        let plays = Play::extract("32T3K 765
+T55X5 684");
+        assert_eq!(plays.map_err(|error| (error.line, error.column, error.text)), Err((2, 4, String::from("X"))));
+
+        let plays = Play::extract("32T3 765");
+        assert_eq!(plays.map_err(|error| error.reason), Err(String::from("Hand must have 5 cards!")));
     }
 
     #[test]
     fn test_new_play() {
         let play = Play::new("32T3K 765");
-        assert_eq!(play, Some(Play {
-            hand: Hand::new("32T3K"),
+        assert_eq!(play, Ok(Play {
+            hand: Hand::new("32T3K").unwrap(),
             bid: 765
         }));
     }
 
     #[test]
     fn test_strength() {
-        let hand = Hand::new("KKKKK");
+        let hand = Hand::new("KKKKK").unwrap();
         assert_eq!(hand.strength(), Some(FiveOfAKind));
-        let hand = Hand::new("99999");
+        let hand = Hand::new("99999").unwrap();
         assert_eq!(hand.strength(), Some(FiveOfAKind));
-        let hand = Hand::new("99599");
+        let hand = Hand::new("99599").unwrap();
         assert_eq!(hand.strength(), Some(FourOfAKind));
-        let hand = Hand::new("KQQQQ");
+        let hand = Hand::new("KQQQQ").unwrap();
         assert_eq!(hand.strength(), Some(FourOfAKind));
-        let hand = Hand::new("333KK");
+        let hand = Hand::new("333KK").unwrap();
         assert_eq!(hand.strength(), Some(FullHouse));
-        let hand = Hand::new("Q3Q3Q");
+        let hand = Hand::new("Q3Q3Q").unwrap();
         assert_eq!(hand.strength(), Some(FullHouse));
-        let hand = Hand::new("4J46J");
+        let hand = Hand::new("4J46J").unwrap();
         assert_eq!(hand.strength(), Some(FourOfAKind));
-        let hand = Hand::new("37583");
+        let hand = Hand::new("37583").unwrap();
         assert_eq!(hand.strength(), Some(Pair));
-        let hand = Hand::new("TJJQK");
+        let hand = Hand::new("TJJQK").unwrap();
         assert_eq!(hand.strength(), Some(ThreeOfAKind));
-        let hand = Hand::new("4ATJ3");
+        let hand = Hand::new("4ATJ3").unwrap();
         assert_eq!(hand.strength(), Some(Pair));
     }
 
@@ -683,17 +793,17 @@ QQQJA 483");
 T55J5 684
 KK677 28
 KTJJT 220
-QQQJA 483");
+QQQJA 483").unwrap();
         let mut plays = plays.iter()
             .map(|play| &play.hand)
             .collect::<Vec<&Hand>>();
         plays.sort();
         assert_eq!(plays, vec![
-            &Hand::new("32T3K"),
-            &Hand::new("KK677"),
-            &Hand::new("T55J5"),
-            &Hand::new("QQQJA"),
-            &Hand::new("KTJJT")
+            &Hand::new("32T3K").unwrap(),
+            &Hand::new("KK677").unwrap(),
+            &Hand::new("T55J5").unwrap(),
+            &Hand::new("QQQJA").unwrap(),
+            &Hand::new("KTJJT").unwrap()
         ]);
     }
 
@@ -703,10 +813,10 @@ QQQJA 483");
         let strength2 = Strength::Pair;
         assert_eq!(strength1.cmp(&strength2), Ordering::Greater);
 
-        let plays = Play::extract("KK677 28");
-        let play1 = plays.get(0).unwrap();
-        let plays = Play::extract("QQQJA 483");
-        let play2 = plays.get(0).unwrap();
+        let plays = Play::extract("KK677 28").unwrap();
+        let play1 = plays.first().unwrap();
+        let plays = Play::extract("QQQJA 483").unwrap();
+        let play2 = plays.first().unwrap();
         let strength1 = play1.hand.strength().unwrap();
         let strength2 = play2.hand.strength().unwrap();
         println!("{:?} < {:?}", strength1, strength2);
@@ -716,14 +826,14 @@ QQQJA 483");
 T55J5 684
 KK677 28
 KTJJT 220
-QQQJA 483");
+QQQJA 483").unwrap();
         plays.sort();
         assert_eq!(plays, vec![
-            Play { bid: 765, hand: Hand::new("32T3K") },
-            Play { bid: 28, hand: Hand::new("KK677") },
-            Play { bid: 684, hand: Hand::new("T55J5") },
-            Play { bid: 483, hand: Hand::new("QQQJA") },
-            Play { bid: 220, hand: Hand::new("KTJJT") }
+            Play { bid: 765, hand: Hand::new("32T3K").unwrap() },
+            Play { bid: 28, hand: Hand::new("KK677").unwrap() },
+            Play { bid: 684, hand: Hand::new("T55J5").unwrap() },
+            Play { bid: 483, hand: Hand::new("QQQJA").unwrap() },
+            Play { bid: 220, hand: Hand::new("KTJJT").unwrap() }
         ]);
 
         let ranks = plays
@@ -736,4 +846,23 @@ QQQJA 483");
             .sum::<u64>();
         assert_eq!(ranks, 5905);
     }
+
+    #[test]
+    fn test_total_winnings_without_jokers() {
+        let plays = Play::extract("32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483").unwrap();
+        let mut plays = plays.iter().collect::<Vec<&Play>>();
+        plays.sort_by(|left, right| left.cmp_without_jokers(right));
+        assert_eq!(plays, vec![
+            &Play { bid: 765, hand: Hand::new("32T3K").unwrap() },
+            &Play { bid: 220, hand: Hand::new("KTJJT").unwrap() },
+            &Play { bid: 28, hand: Hand::new("KK677").unwrap() },
+            &Play { bid: 684, hand: Hand::new("T55J5").unwrap() },
+            &Play { bid: 483, hand: Hand::new("QQQJA").unwrap() }
+        ]);
+        assert_eq!(total_winnings(&plays), 6440);
+    }
 }
\ No newline at end of file
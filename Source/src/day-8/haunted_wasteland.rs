@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use crate::parse_error::ParseError;
+use crate::solution::Solution;
 
 #[derive(Debug, PartialEq)]
 pub struct Network {
@@ -7,17 +9,27 @@ pub struct Network {
 }
 
 impl Network {
-    pub fn new(s: &str) -> Option<Network> {
+    pub fn new(s: &str) -> Result<Network, ParseError> {
         let mut lines = s.lines();
-        let instructions = lines.next()?;
-        let instructions = Instruction::extract(instructions);
-        let lines = lines.skip(1);
+        let instructions = Instruction::extract(lines.next().unwrap_or(s))?;
+
+        if instructions.is_empty() {
+            return Err(ParseError::new(Day8::DAY, s, s, "Network must start with a line of 'L' and 'R' instructions!"));
+        }
+
+        match lines.next() {
+            Some(line) if !line.is_empty() =>
+                return Err(ParseError::new(Day8::DAY, line, line, "Instructions must be followed by an empty line!").on_line(1)),
+            _ => {}
+        }
+
         let nodes = lines
-            .filter_map(Node::new)
-            .map(|node| (node.name.clone(), node))
-            .collect::<HashMap<String, Node>>();
+            .enumerate()
+            .map(|(index, line)| Node::new(line).map_err(|error| error.on_line(index + 2)))
+            .map(|node| node.map(|node| (node.name.clone(), node)))
+            .collect::<Result<HashMap<String, Node>, ParseError>>()?;
 
-        Some(Network { instructions, nodes })
+        Ok(Network { instructions, nodes })
     }
 
     pub fn run_instructions(&self, first_node: &str) -> Option<Path<'_>> {
@@ -28,7 +40,7 @@ impl Network {
         let mut instruction = instructions.next()?;
         while !node.name.ends_with("Z") {
             nodes.push(node);
-            node = self.navigate(&node, &instruction)?;
+            node = self.navigate(node, instruction)?;
             instruction = match instructions.next() {
                 None => {
                     instructions = self.instructions.iter();
@@ -51,6 +63,13 @@ impl Network {
         self.nodes.get(to)
     }
 
+    pub fn ghost_steps(&self) -> Option<u64> {
+        self.starting_nodes()
+            .iter()
+            .map(|node| self.run_instructions(&node.name).map(|path| path.len() as u64))
+            .try_fold(1_u64, |least_common_multiple, loop_length| Some(lcm(least_common_multiple, loop_length?)))
+    }
+
     pub fn starting_nodes(&self) -> Vec<&Node> {
         self.nodes
             .iter()
@@ -70,6 +89,10 @@ impl Path<'_> {
     pub fn len(&self) -> usize {
         self.nodes.len() - 1
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -80,31 +103,28 @@ pub struct Node {
 }
 
 impl Node {
-    fn new(s: &str) -> Option<Node> {
-        let mut def_parts = s.split('=');
-        let name = def_parts
-            .next()?
+    fn new(s: &str) -> Result<Node, ParseError> {
+        let (name, instructions) = match s.split_once('=') {
+            Some(def_parts) => def_parts,
+            None => return Err(ParseError::new(Day8::DAY, s, s, "Node must be '<name> = (<left>, <right>)'!"))
+        };
+        let name = name
             .trim()
             .to_string();
-        let instructions = def_parts
-            .next()?
-            .trim();
-
-        if instructions.len() < 2 {
-            return None;
-        }
-
-        let mut instruction_parts = instructions[1..instructions.len()-1]
-            .split(',');
-        let left = instruction_parts
-            .next()?
+        let instructions = instructions.trim();
+        let (left, right) = instructions
+            .strip_prefix('(')
+            .and_then(|instructions| instructions.strip_suffix(')'))
+            .and_then(|instructions| instructions.split_once(','))
+            .ok_or_else(|| ParseError::new(Day8::DAY, s, instructions, "Node destinations must be '(<left>, <right>)'!"))?;
+        let left = left
             .trim()
             .to_string();
-        let right = instruction_parts.next()?
+        let right = right
             .trim()
             .to_string();
 
-        Some(Self { name, left, right })
+        Ok(Self { name, left, right })
     }
 
     pub fn name(&self) -> String {
@@ -119,8 +139,12 @@ enum Instruction {
 }
 
 impl Instruction {
-    fn extract(s: &str) -> Vec<Self> {
-        s.chars().filter_map(Self::new).collect::<Vec<Instruction>>()
+    fn extract(s: &str) -> Result<Vec<Self>, ParseError> {
+        s
+            .char_indices()
+            .map(|(index, c)| Self::new(c).ok_or_else(|| ParseError::new(
+                Day8::DAY, s, &s[index..index + c.len_utf8()], "Instruction must be 'L' or 'R'!")))
+            .collect::<Result<Vec<Instruction>, ParseError>>()
     }
 
     fn new(c: char) -> Option<Self> {
@@ -132,6 +156,41 @@ impl Instruction {
     }
 }
 
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    (a / gcd(a, b)) * b
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Haunted Wasteland";
+
+    type Input<'a> = Network;
+    type Part1 = usize;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Network::new(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        input.run_instructions("AAA").map(|path| path.len())
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        input.ghost_steps()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,7 +206,7 @@ DDD = (DDD, DDD)
 EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)");
-        assert_eq!(network, Some(
+        assert_eq!(network, Ok(
             Network {
                 instructions: vec![
                     Instruction::Right,
@@ -170,7 +229,7 @@ ZZZ = (ZZZ, ZZZ)");
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)");
-        assert_eq!(network, Some(
+        assert_eq!(network, Ok(
             Network {
                 instructions: vec![
                     Instruction::Left,
@@ -184,6 +243,13 @@ ZZZ = (ZZZ, ZZZ)");
                 ])
             }
         ));
+
+        let network = Network::new("LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA ZZZ)
+ZZZ = (ZZZ, ZZZ)");
+        assert_eq!(network.map_err(|error| (error.line, error.column)), Err((4, 7)));
     }
 
     #[test]
@@ -243,9 +309,9 @@ ZZZ = (ZZZ, ZZZ)").unwrap();
         let path = network.run_instructions("AAA").unwrap();
         assert_eq!(path.len(), 6);
 
-        let network = Network::new("LLR
+        let network = Network::new("R
 
-AAA = (BBB, BBB)
+AAA = (BBB, ZZZ)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)").unwrap();
         let path = network.run_instructions("AAA").unwrap();
@@ -257,18 +323,24 @@ ZZZ = (ZZZ, ZZZ)").unwrap();
     #[test]
     fn test_node_new() {
         let node = Node::new("AAA = (BBB, CCC)");
-        assert_eq!(node, Some(Node {
+        assert_eq!(node, Ok(Node {
             name: String::from("AAA"),
             left: String::from("BBB"),
             right: String::from("CCC")
         }));
 
         let node = Node::new("ZZZ = (ZZZ, ZZZ)");
-        assert_eq!(node, Some(Node {
+        assert_eq!(node, Ok(Node {
             name: String::from("ZZZ"),
             left: String::from("ZZZ"),
             right: String::from("ZZZ")
         }));
+
+        let node = Node::new("ZZZ = ZZZ, ZZZ");
+        assert_eq!(node.map_err(|error| (error.column, error.text)), Err((7, String::from("ZZZ, ZZZ"))));
+
+        let node = Node::new("ZZZ (ZZZ, ZZZ)");
+        assert!(node.is_err());
     }
 
     #[test]
@@ -286,16 +358,34 @@ ZZZ = (ZZZ, ZZZ)").unwrap();
     #[test]
     fn test_instruction_extract() {
         let instructions = Instruction::extract("RL");
-        assert_eq!(instructions, vec![
+        assert_eq!(instructions, Ok(vec![
             Instruction::Right,
             Instruction::Left
-        ]);
+        ]));
 
         let instructions = Instruction::extract("LLR");
-        assert_eq!(instructions, vec![
+        assert_eq!(instructions, Ok(vec![
             Instruction::Left,
             Instruction::Left,
             Instruction::Right
-        ]);
+        ]));
+
+        let instructions = Instruction::extract("LLXR");
+        assert_eq!(instructions.map_err(|error| (error.column, error.text)), Err((3, String::from("X"))));
+    }
+
+    #[test]
+    fn test_network_ghost_steps() {
+        let network = Network::new("LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)").unwrap();
+        assert_eq!(network.ghost_steps(), Some(6));
     }
 }
\ No newline at end of file
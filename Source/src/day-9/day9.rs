@@ -1,17 +1,7 @@
-use std::{env, fs, path};
-use crate::mirage_maintenance::History;
+// https://adventofcode.com/2023/day/9
 
-mod mirage_maintenance;
+use adventofcode_2023::cli;
 
 fn main() {
-    let mut args = env::args();
-    _ = args.next();
-    let file_path = args.next().unwrap();
-    let file_path = path::Path::new(&file_path);
-    let file_content = fs::read_to_string(file_path).unwrap();
-    let historical_data = History::extract(&file_content);
-    let oasis_report_sum = historical_data.iter().map(|history| history.predict()).sum::<i64>();
-    println!("OASIS r-report sum is {oasis_report_sum}");
-    let oasis_report_sum = historical_data.iter().map(|history| history.lpredict()).sum::<i64>();
-    println!("OASIS l-report sum is {oasis_report_sum}");
+    cli::run_day(9);
 }
\ No newline at end of file
@@ -1,23 +1,24 @@
+use crate::parse_error::{parse_numbers, ParseError};
+use crate::solution::Solution;
+
 #[derive(Debug, PartialEq)]
 pub struct History {
     values: Vec<i64>
 }
 
 impl History {
-    fn new(s: &str) -> Option<Self> {
-        let values = s
-            .split(" ")
-            .filter_map(|value| value.parse::<i64>().ok())
-            .collect::<Vec<i64>>();
+    fn new(s: &str) -> Result<Self, ParseError> {
+        let values = parse_numbers::<i64>(Day9::DAY, s, s)?;
 
-        Some(History { values })
+        Ok(History { values })
     }
 
-    pub fn extract(s: &str) -> Vec<Self> {
+    pub fn extract(s: &str) -> Result<Vec<Self>, ParseError> {
         s
             .lines()
-            .filter_map(Self::new)
-            .collect::<Vec<Self>>()
+            .enumerate()
+            .map(|(index, line)| Self::new(line).map_err(|error| error.on_line(index)))
+            .collect::<Result<Vec<Self>, ParseError>>()
     }
 
     fn subsequence(&self) -> Self {
@@ -26,7 +27,7 @@ impl History {
         }
 
         let mut values = Vec::<i64>::new();
-        let mut prev_value = self.values.get(0).unwrap();
+        let mut prev_value = self.values.first().unwrap();
 
         for i in 1..self.values.len() {
             let value = self.values.get(i).unwrap();
@@ -65,7 +66,7 @@ impl History {
         }
     }
 
-    fn extrapolate(&self) -> Self {
+    pub fn extrapolate(&self) -> Self {
         let predicted_value = self.predict();
         let mut values = self.values.clone();
         values.push(predicted_value);
@@ -74,6 +75,29 @@ impl History {
     }
 }
 
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Mirage Maintenance";
+
+    type Input<'a> = Vec<History>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        History::extract(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1> {
+        Some(input.iter().map(History::predict).sum())
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2> {
+        Some(input.iter().map(History::lpredict).sum())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,19 +105,22 @@ mod tests {
     #[test]
     fn test_history_new() {
         let history = History::new("0 3 6 9 12 15");
-        assert_eq!(history, Some(History {
+        assert_eq!(history, Ok(History {
             values: vec![0, 3, 6, 9, 12, 15]
         }));
 
         let history = History::new("1 3 6 10 15 21");
-        assert_eq!(history, Some(History {
+        assert_eq!(history, Ok(History {
             values: vec![1, 3, 6, 10, 15, 21]
         }));
 
         let history = History::new("10 13 16 21 30 45");
-        assert_eq!(history, Some(History {
+        assert_eq!(history, Ok(History {
             values: vec![10, 13, 16, 21, 30, 45]
         }));
+
+        let history = History::new("10 13 x 21");
+        assert_eq!(history.map_err(|error| (error.column, error.text)), Err((7, String::from("x"))));
     }
 
     #[test]
@@ -101,11 +128,15 @@ mod tests {
         let historical_data = History::extract("0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45");
-        assert_eq!(historical_data, vec![
+        assert_eq!(historical_data, Ok(vec![
             History::new("0 3 6 9 12 15").unwrap(),
             History::new("1 3 6 10 15 21").unwrap(),
             History::new("10 13 16 21 30 45").unwrap()
-        ]);
+        ]));
+
+        let historical_data = History::extract("0 3 6 9 12 15
+1 3 6 10 1.5 21");
+        assert_eq!(historical_data.map_err(|error| (error.line, error.column)), Err((2, 10)));
     }
 
     #[test]
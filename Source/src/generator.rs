@@ -0,0 +1,527 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use crate::cube_conundrum::{Cubes, CubesSet, Game};
+
+/// Small seeded random number generator (SplitMix64).
+///
+/// Hand-rolled instead of pulling in `rand`, so a seed produces the same input on every platform
+/// and compiler version.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random number in `min..=max`.
+    pub fn between(&mut self, min: u64, max: u64) -> u64 {
+        min + self.next_u64() % (max - min + 1)
+    }
+
+    /// Random index below `len`, which must not be 0.
+    pub fn index(&mut self, len: usize) -> usize {
+        self.between(0, len as u64 - 1) as usize
+    }
+
+    /// `true` with the probability `percent` / 100.
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.between(1, 100) <= percent
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// Generates an input for `day` that the day's parser accepts, `None` if there's no generator for
+/// the day. The meaning of `size` depends on the day, see the individual generators.
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    let size = size.max(1);
+
+    match day {
+        1 => Some(trebuchet(rng, size)),
+        2 => Some(cube_conundrum(rng, size)),
+        3 => Some(gear_ratios(rng, size)),
+        4 => Some(scratchcards(rng, size)),
+        5 => Some(seed_fertilizer(rng, size)),
+        6 => Some(wait_for_it(rng, size)),
+        7 => Some(camel_cards(rng, size)),
+        8 => Some(haunted_wasteland(rng, size)),
+        9 => Some(mirage_maintenance(rng, size)),
+        10 => Some(pipe_maze(rng, size)),
+        _ => None
+    }
+}
+
+const DIGIT_NAMES: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+/// `lines` calibration lines mixing letters, digits and spelled out digits, each with at least one digit.
+pub fn trebuchet(rng: &mut Rng, lines: usize) -> String {
+    let mut input = String::new();
+
+    for _ in 0..lines {
+        let mut line = String::new();
+        let digit_position = rng.between(0, 5);
+
+        for position in 0..rng.between(digit_position + 1, 8) {
+            if position == digit_position || rng.chance(20) {
+                line.push(char::from(b'0' + rng.between(1, 9) as u8));
+            } else if rng.chance(30) {
+                let name = rng.choose(&DIGIT_NAMES);
+                line.push_str(name);
+            } else {
+                line.push(char::from(b'a' + rng.between(0, 25) as u8));
+            }
+        }
+
+        writeln!(input, "{line}").unwrap();
+    }
+
+    input
+}
+
+/// `games` games of one to six draws of red, green and blue cubes.
+pub fn cube_conundrum(rng: &mut Rng, games: usize) -> String {
+    let mut input = String::new();
+
+    for id in 1..=games {
+        let sets = (0..rng.between(1, 6))
+            .map(|_| {
+                let mut kinds = ["red", "green", "blue"];
+                rng.shuffle(&mut kinds);
+                let cubes = kinds[..rng.between(1, 3) as usize]
+                    .iter()
+                    .map(|kind| Cubes::new(kind, rng.between(1, 20) as u32))
+                    .collect::<Vec<Cubes>>();
+                CubesSet::new(cubes)
+            })
+            .collect::<Vec<CubesSet>>();
+
+        writeln!(input, "{}", Game::new(id as u32, sets)).unwrap();
+    }
+
+    input
+}
+
+const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '=', '%', '@', '&', '-'];
+
+/// Square engine schematic with `size` rows of numbers and symbols.
+pub fn gear_ratios(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(3);
+    let mut input = String::new();
+
+    for _ in 0..size {
+        let mut row = String::new();
+
+        while row.len() < size {
+            let remaining = size - row.len();
+            if remaining > 3 && rng.chance(25) {
+                let number = rng.between(1, 999).to_string();
+                let number = &number[..number.len().min(remaining - 1)];
+                row.push_str(number);
+                row.push('.');
+            } else if rng.chance(10) {
+                row.push(*rng.choose(&SYMBOLS));
+            } else {
+                row.push('.');
+            }
+        }
+
+        writeln!(input, "{row}").unwrap();
+    }
+
+    input
+}
+
+/// `cards` scratchcards with 10 winning and 25 pulled numbers, matches never win copies of cards
+/// past the end of the table.
+pub fn scratchcards(rng: &mut Rng, cards: usize) -> String {
+    let mut input = String::new();
+    let width = cards.to_string().len();
+
+    for index in 0..cards {
+        let mut numbers = (1..=99).collect::<Vec<u64>>();
+        rng.shuffle(&mut numbers);
+        let winning_numbers = &numbers[..10];
+        let match_count = (rng.between(0, 10) as usize).min(cards - index - 1);
+        let mut pulled_numbers = winning_numbers[..match_count]
+            .iter()
+            .chain(numbers[10..35 - match_count].iter())
+            .copied()
+            .collect::<Vec<u64>>();
+        rng.shuffle(&mut pulled_numbers);
+
+        writeln!(input, "Card {:>width$}: {} | {}", index + 1, format_numbers(winning_numbers), format_numbers(&pulled_numbers))
+            .unwrap();
+    }
+
+    input
+}
+
+fn format_numbers(numbers: &[u64]) -> String {
+    numbers
+        .iter()
+        .map(|number| format!("{number:>2}"))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+const ALMANAC_CATEGORIES: [&str; 8] = ["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"];
+
+/// Almanac with `size` seed ranges and up to `size` ranges per map, in a value space of about
+/// `size` million. Seed ranges stay short, so the brute force of part 2 remains feasible.
+pub fn seed_fertilizer(rng: &mut Rng, size: usize) -> String {
+    let space = size as u64 * 1_000_000;
+    let seeds = (0..size)
+        .map(|_| format!("{} {}", rng.between(0, space), rng.between(1, 1000)))
+        .collect::<Vec<String>>();
+    let mut input = format!("seeds: {}\n", seeds.join(" "));
+
+    for categories in ALMANAC_CATEGORIES.windows(2) {
+        write!(input, "\n{}-to-{} map:\n", categories[0], categories[1]).unwrap();
+
+        let ranges = rng.between(1, size as u64);
+        let mut source_start = 0;
+        for _ in 0..ranges {
+            source_start += rng.between(0, space / ranges);
+            let length = rng.between(1, space / ranges);
+            writeln!(input, "{} {source_start} {length}", rng.between(0, space)).unwrap();
+            source_start += length + 1;
+        }
+    }
+
+    input
+}
+
+/// Up to four races, more would overflow the single race of part 2.
+pub fn wait_for_it(rng: &mut Rng, races: usize) -> String {
+    let (times, distances) = (0..races.min(4))
+        .map(|_| {
+            let time = rng.between(7, 99);
+            let record = (time / 2) * (time - time / 2);
+            (time, rng.between(0, record - 1))
+        })
+        .unzip::<u64, u64, Vec<u64>, Vec<u64>>();
+
+    format!("Time:     {}\nDistance: {}\n", format_columns(&times), format_columns(&distances))
+}
+
+fn format_columns(values: &[u64]) -> String {
+    values
+        .iter()
+        .map(|value| format!("{value:>4}"))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// `hands` distinct hands with bids up to 1000, at most every possible hand.
+pub fn camel_cards(rng: &mut Rng, hands: usize) -> String {
+    let cards = "AKQJT98765432".chars().collect::<Vec<char>>();
+    let hands = hands.min(cards.len().pow(5));
+    let mut seen = HashSet::<String>::new();
+    let mut input = String::new();
+
+    while seen.len() < hands {
+        let hand = (0..5)
+            .map(|_| *rng.choose(&cards))
+            .collect::<String>();
+        if seen.insert(hand.clone()) {
+            writeln!(input, "{hand} {}", rng.between(1, 1000)).unwrap();
+        }
+    }
+
+    input
+}
+
+const GHOST_CYCLE_FACTORS: [usize; 6] = [2, 3, 5, 7, 11, 13];
+
+/// Network with `size` instructions (at most 500) and one to four ghosts, starting with the one
+/// walking from `AAA` to `ZZZ`.
+///
+/// Like the puzzle inputs, every ghost walks a cycle whose length is the number of instructions
+/// times a distinct prime, and reaches its `Z` node exactly once per cycle.
+pub fn haunted_wasteland(rng: &mut Rng, size: usize) -> String {
+    let instructions = (0..size.clamp(1, 500))
+        .map(|_| if rng.chance(50) { 'L' } else { 'R' })
+        .collect::<Vec<char>>();
+    let mut factors = GHOST_CYCLE_FACTORS;
+    rng.shuffle(&mut factors);
+    let ghosts = rng.between(1, 4) as usize;
+    let mut names = HashSet::<String>::from([String::from("AAA"), String::from("ZZZ")]);
+    let mut cycles = Vec::<Vec<String>>::new();
+
+    for (ghost, factor) in factors[..ghosts].iter().enumerate() {
+        let (start, end) = if ghost == 0 {
+            (String::from("AAA"), String::from("ZZZ"))
+        } else {
+            let prefix = unique_name(rng, &mut names, |name| name.ends_with('A') || name.ends_with('Z'));
+            let prefix = &prefix[..2];
+            let (start, end) = (format!("{prefix}A"), format!("{prefix}Z"));
+            if names.contains(&start) || names.contains(&end) {
+                continue;
+            }
+
+            names.insert(start.clone());
+            names.insert(end.clone());
+            (start, end)
+        };
+
+        let mut cycle = vec![start];
+        for _ in 1..instructions.len() * factor {
+            cycle.push(unique_name(rng, &mut names, |name| name.ends_with('A') || name.ends_with('Z')));
+        }
+        cycle.push(end);
+        cycles.push(cycle);
+    }
+
+    let mut all_names = names
+        .iter()
+        .cloned()
+        .collect::<Vec<String>>();
+    all_names.sort();
+    let mut nodes = HashMap::<String, (String, String)>::new();
+
+    for cycle in &cycles {
+        for (step, name) in cycle.iter().enumerate() {
+            // The end node continues with the second node of the cycle, so the cycle repeats.
+            let next = if step + 1 == cycle.len() { &cycle[1] } else { &cycle[step + 1] };
+            let decoy = rng.choose(&all_names).clone();
+            let destinations = match instructions[step % instructions.len()] {
+                'L' => (next.clone(), decoy),
+                _ => (decoy, next.clone())
+            };
+            nodes.insert(name.clone(), destinations);
+        }
+    }
+
+    let mut lines = nodes
+        .iter()
+        .map(|(name, (left, right))| format!("{name} = ({left}, {right})"))
+        .collect::<Vec<String>>();
+    lines.sort();
+    rng.shuffle(&mut lines);
+
+    format!("{}\n\n{}\n", instructions.iter().collect::<String>(), lines.join("\n"))
+}
+
+/// Random unused three letter node name, rejecting names matching `reserved`.
+fn unique_name(rng: &mut Rng, names: &mut HashSet<String>, reserved: impl Fn(&str) -> bool) -> String {
+    loop {
+        let name = (0..3)
+            .map(|_| char::from(b'A' + rng.between(0, 25) as u8))
+            .collect::<String>();
+        if !reserved(&name) && names.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+/// `histories` histories of 21 values of polynomials up to the fifth degree.
+pub fn mirage_maintenance(rng: &mut Rng, histories: usize) -> String {
+    let mut input = String::new();
+
+    for _ in 0..histories {
+        let coefficients = (0..=rng.between(0, 5))
+            .map(|_| rng.between(0, 20) as i64 - 10)
+            .collect::<Vec<i64>>();
+        let values = (0..21_i64)
+            .map(|x| coefficients
+                .iter()
+                .rev()
+                .fold(0, |value, coefficient| value * x + coefficient))
+            .map(|value| value.to_string())
+            .collect::<Vec<String>>();
+
+        writeln!(input, "{}", values.join(" ")).unwrap();
+    }
+
+    input
+}
+
+/// Maze of `size` x `size` blocks of 3 x 3 tiles, with junk pipes around the loop.
+///
+/// The loop runs along the border of every block of a random tree of blocks, so it always has
+/// 8 tiles and encloses exactly one tile per block: the farthest tile is 4 steps per block away.
+pub fn pipe_maze(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let blocks = rng.between(1, (size * size) as u64) as usize;
+    let tree = random_tree(rng, size, blocks);
+    let width = size * 3;
+    let mut connections = HashSet::<((usize, usize), (usize, usize))>::new();
+    let mut connect = |a: (usize, usize), b: (usize, usize), add: bool| {
+        let edge = if a < b { (a, b) } else { (b, a) };
+        if add { connections.insert(edge); } else { connections.remove(&edge); }
+    };
+
+    for &(x, y) in tree.keys() {
+        let (left, top) = (x * 3, y * 3);
+        for i in 0..2 {
+            connect((left + i, top), (left + i + 1, top), true);
+            connect((left + i, top + 2), (left + i + 1, top + 2), true);
+            connect((left, top + i), (left, top + i + 1), true);
+            connect((left + 2, top + i), (left + 2, top + i + 1), true);
+        }
+    }
+
+    // Merges the loops of neighboring blocks by swapping two parallel pipes for two bridges.
+    for (&(x, y), &parent) in &tree {
+        let ((x, y), (other_x, _)) = match parent {
+            None => continue,
+            Some(parent) => if parent < (x, y) { (parent, (x, y)) } else { ((x, y), parent) }
+        };
+        let (left, top) = (x * 3, y * 3);
+        if other_x > x {
+            connect((left + 2, top), (left + 2, top + 1), false);
+            connect((left + 3, top), (left + 3, top + 1), false);
+            connect((left + 2, top), (left + 3, top), true);
+            connect((left + 2, top + 1), (left + 3, top + 1), true);
+        } else {
+            connect((left, top + 2), (left + 1, top + 2), false);
+            connect((left, top + 3), (left + 1, top + 3), false);
+            connect((left, top + 2), (left, top + 3), true);
+            connect((left + 1, top + 2), (left + 1, top + 3), true);
+        }
+    }
+
+    let mut tiles = (0..width)
+        .map(|_| (0..width)
+            .map(|_| if rng.chance(40) { '.' } else { *rng.choose(&['|', '-', 'L', 'J', '7', 'F']) })
+            .collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+    let mut loop_tiles = Vec::<(usize, usize)>::new();
+
+    for &(x, y) in tree.keys() {
+        let block_tiles = (y * 3..y * 3 + 3).flat_map(|tile_y| (x * 3..x * 3 + 3).map(move |tile_x| (tile_x, tile_y)));
+        for (tile_x, tile_y) in block_tiles {
+            let connected = |other: Option<(usize, usize)>| other.is_some_and(|other| {
+                let edge = if (tile_x, tile_y) < other { ((tile_x, tile_y), other) } else { (other, (tile_x, tile_y)) };
+                connections.contains(&edge)
+            });
+            let north = connected(tile_y.checked_sub(1).map(|north| (tile_x, north)));
+            let south = connected(Some((tile_x, tile_y + 1)));
+            let west = connected(tile_x.checked_sub(1).map(|west| (west, tile_y)));
+            let east = connected(Some((tile_x + 1, tile_y)));
+            let tile = match (north, south, west, east) {
+                (true, true, _, _) => '|',
+                (_, _, true, true) => '-',
+                (true, _, _, true) => 'L',
+                (true, _, true, _) => 'J',
+                (_, true, true, _) => '7',
+                (_, true, _, true) => 'F',
+                _ => '.'
+            };
+            if tile == '.' {
+                tiles[tile_y][tile_x] = *rng.choose(&['.', '.', '|', '-', 'L', 'J', '7', 'F']);
+            } else {
+                tiles[tile_y][tile_x] = tile;
+                loop_tiles.push((tile_x, tile_y));
+            }
+        }
+    }
+
+    let (start_x, start_y) = *rng.choose(&loop_tiles);
+    tiles[start_y][start_x] = 'S';
+
+    tiles
+        .iter()
+        .map(|row| format!("{}\n", row.iter().collect::<String>()))
+        .collect::<String>()
+}
+
+/// Random tree of `blocks` neighboring blocks in a `size` x `size` grid, mapping every block to
+/// its parent.
+fn random_tree(rng: &mut Rng, size: usize, blocks: usize) -> BTreeMap<(usize, usize), Option<(usize, usize)>> {
+    let root = (rng.index(size), rng.index(size));
+    let mut tree = BTreeMap::<(usize, usize), Option<(usize, usize)>>::from([(root, None)]);
+    let mut frontier = vec![root];
+
+    while tree.len() < blocks && !frontier.is_empty() {
+        let index = rng.index(frontier.len());
+        let (x, y) = frontier[index];
+        let neighbors = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)]
+            .into_iter()
+            .filter(|&(x, y)| x < size && y < size && !tree.contains_key(&(x, y)))
+            .collect::<Vec<(usize, usize)>>();
+
+        if neighbors.is_empty() {
+            frontier.swap_remove(index);
+        } else {
+            let neighbor = *rng.choose(&neighbors);
+            tree.insert(neighbor, Some((x, y)));
+            frontier.push(neighbor);
+        }
+    }
+
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipe_maze::Maze;
+    use crate::solution::Parts;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(42);
+        let values = (0..100).map(|_| rng.between(3, 7)).collect::<Vec<u64>>();
+        assert!(values.iter().all(|value| (3..=7).contains(value)));
+        assert!((3..=7).all(|value| values.contains(&value)));
+
+        let mut items = [1, 2, 3, 4, 5];
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        for day in 1..=10 {
+            assert_eq!(generate(day, 7, 12), generate(day, 7, 12));
+            assert_ne!(generate(day, 7, 12), generate(day, 8, 12));
+        }
+
+        assert_eq!(generate(11, 7, 12), None);
+    }
+
+    #[test]
+    fn test_generate_parses() {
+        for solver in crate::solvers() {
+            for seed in 0..5 {
+                for size in [1, 3, 12] {
+                    let input = generate(solver.day(), seed, size).unwrap();
+                    let answers = solver.solve(&input, Parts::Both)
+                        .unwrap_or_else(|error| panic!("Seed {seed}, size {size}: {error}\n{input}"));
+                    assert!(answers.iter().all(|answer| answer.value.is_some()), "Seed {seed}, size {size}:\n{input}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pipe_maze() {
+        for seed in 0..20 {
+            let maze = Maze::new(&pipe_maze(&mut Rng::new(seed), 6)).unwrap();
+            let enclosed_tile_count = maze.enclosed_tile_count().unwrap();
+            assert_eq!(maze.farthest_distance().unwrap() as u64, enclosed_tile_count * 4);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,63 @@
+pub mod answers;
+pub mod bench;
+pub mod generator;
+pub mod parse_error;
+pub mod solution;
+pub mod warnings;
+
+#[path = "day-1/trebuchet.rs"]
+pub mod trebuchet;
+#[path = "day-2/cube_conundrum.rs"]
+pub mod cube_conundrum;
+#[path = "day-2/game_query.rs"]
+pub mod game_query;
+#[path = "day-3/gear_ratios.rs"]
+pub mod gear_ratios;
+#[path = "day-3/schematic_editor.rs"]
+pub mod schematic_editor;
+#[path = "day-3/schematic_render.rs"]
+pub mod schematic_render;
+#[path = "day-4/scratchcards.rs"]
+// `Scratchcard::from_str` reads every card of an input, not a single one.
+#[allow(clippy::should_implement_trait)]
+pub mod scratchcards;
+#[path = "day-5/seed_fertilizer.rs"]
+pub mod seed_fertilizer;
+#[path = "day-6/wait_for_it.rs"]
+pub mod wait_for_it;
+#[path = "day-7/camel_cards.rs"]
+// The hand ranking below is kept as it was written for the puzzle, lints included.
+#[allow(dead_code, clippy::derived_hash_with_manual_eq, clippy::non_canonical_partial_ord_impl,
+    clippy::if_same_then_else, clippy::match_like_matches_macro)]
+pub mod camel_cards;
+#[path = "day-8/haunted_wasteland.rs"]
+pub mod haunted_wasteland;
+#[path = "day-9/mirage_maintenance.rs"]
+pub mod mirage_maintenance;
+#[path = "day-10/pipe_maze.rs"]
+pub mod pipe_maze;
+
+pub use parse_error::ParseError;
+pub use solution::Solution;
+
+pub mod cli;
+
+/// All solved days, ordered by day.
+pub fn solvers() -> Vec<Box<dyn solution::Solver>> {
+    vec![
+        solution::solver::<trebuchet::Day1>(),
+        solution::solver::<cube_conundrum::Day2>(),
+        solution::solver::<gear_ratios::Day3>(),
+        solution::solver::<scratchcards::Day4>(),
+        solution::solver::<seed_fertilizer::Day5>(),
+        solution::solver::<wait_for_it::Day6>(),
+        solution::solver::<camel_cards::Day7>(),
+        solution::solver::<haunted_wasteland::Day8>(),
+        solution::solver::<mirage_maintenance::Day9>(),
+        solution::solver::<pipe_maze::Day10>()
+    ]
+}
+
+pub fn find_solver(day: u8) -> Option<Box<dyn solution::Solver>> {
+    solvers().into_iter().find(|solver| solver.day() == day)
+}
@@ -0,0 +1,126 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Error for malformed puzzle input.
+///
+/// Lines and columns are 1-based, columns count bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String
+}
+
+impl ParseError {
+    /// Creates an error for `text`, which should be a slice of `str`, located relative to `str`.
+    pub fn new(day: u8, str: &str, text: &str, reason: impl Into<String>) -> Self {
+        let (line, column) = position(str, text);
+        Self {
+            day,
+            line,
+            column,
+            text: String::from(text),
+            reason: reason.into()
+        }
+    }
+
+    /// Relocates an error found in `inner`, which should be a slice of `outer`, so that it's
+    /// relative to `outer`.
+    pub fn within(self, outer: &str, inner: &str) -> Self {
+        let (line, column) = position(outer, inner);
+        if self.line == 1 {
+            Self { line, column: column + self.column - 1, ..self }
+        } else {
+            Self { line: line + self.line - 1, ..self }
+        }
+    }
+
+    /// Moves an error found in a single line to the line with the 0-based `line_index`.
+    pub fn on_line(self, line_index: usize) -> Self {
+        Self { line: self.line + line_index, ..self }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid day {} input at line {}, column {}: {} (found \"{}\")",
+               self.day, self.line, self.column, self.reason, self.text)
+    }
+}
+
+impl Error for ParseError {
+}
+
+/// Parses whitespace separated numbers, where `numbers` should be a slice of `line`.
+pub fn parse_numbers<T>(day: u8, line: &str, numbers: &str) -> Result<Vec<T>, ParseError>
+where T: FromStr, T::Err: Display {
+    numbers
+        .split_whitespace()
+        .map(|number| number
+            .parse::<T>()
+            .map_err(|error| ParseError::new(day, line, number, format!("Unable to parse number! {error}"))))
+        .collect::<Result<Vec<T>, ParseError>>()
+}
+
+fn position(outer: &str, inner: &str) -> (usize, usize) {
+    let start = outer.as_ptr() as usize;
+    let inner_start = inner.as_ptr() as usize;
+    if inner_start < start || inner_start > start + outer.len() {
+        return (1, 1);
+    }
+
+    let preceding = &outer[..inner_start - start];
+    match preceding.rfind('\n') {
+        None => (1, preceding.len() + 1),
+        Some(newline_index) => (preceding.matches('\n').count() + 1, preceding.len() - newline_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let line = "Game 1: 3 blue, x red";
+        let error = ParseError::new(2, line, &line[16..17], "Unable to parse cubes count");
+        assert_eq!(error, ParseError {
+            day: 2,
+            line: 1,
+            column: 17,
+            text: String::from("x"),
+            reason: String::from("Unable to parse cubes count")
+        });
+
+        let error = ParseError::new(2, line, &String::from("x"), "Unable to parse cubes count");
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn test_within() {
+        let input = "seeds: 1 2\n\nseed-to-soil map:\n50 x 2";
+        let block = &input[12..];
+        let line = &block[18..];
+        let error = ParseError::new(5, line, &line[3..4], "Unable to parse number")
+            .within(block, line)
+            .within(input, block);
+        assert_eq!((error.line, error.column), (4, 4));
+    }
+
+    #[test]
+    fn test_on_line() {
+        let line = "1 2 x";
+        let error = ParseError::new(9, line, &line[4..], "Unable to parse value").on_line(4);
+        assert_eq!((error.line, error.column), (5, 5));
+    }
+
+    #[test]
+    fn test_display() {
+        let line = "Card 1: 1 2 | 3 x";
+        let error = ParseError::new(4, line, &line[16..], "Unable to parse number").on_line(2);
+        assert_eq!(error.to_string(), "Invalid day 4 input at line 3, column 17: Unable to parse number (found \"x\")");
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use crate::parse_error::ParseError;
+use crate::warnings;
+
+/// A puzzle solver for a single day.
+///
+/// The input is parsed once by [`Solution::parse`] and then handed to both parts, so parts can
+/// share the (sometimes expensive) parsed representation.
+pub trait Solution {
+    /// Day of the Advent of Code calendar this solution belongs to.
+    const DAY: u8;
+    /// Title of the puzzle as it appears on the Advent of Code website.
+    const TITLE: &'static str;
+
+    type Input<'a>;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError>;
+
+    /// Returns `None` if the input doesn't have an answer for the first part.
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Part1>;
+
+    /// Returns `None` if the input doesn't have an answer for the second part.
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Part2>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two
+}
+
+impl Part {
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None
+        }
+    }
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parts {
+    One,
+    Two,
+    Both
+}
+
+impl FromStr for Parts {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        match str {
+            "1" => Ok(Parts::One),
+            "2" => Ok(Parts::Two),
+            "both" => Ok(Parts::Both),
+            _ => Err(format!("Unknown part {str}! Part must be 1, 2 or both."))
+        }
+    }
+}
+
+impl Parts {
+    pub fn contains(&self, part: Part) -> bool {
+        match self {
+            Parts::One => part == Part::One,
+            Parts::Two => part == Part::Two,
+            Parts::Both => true
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Answer {
+    pub part: Part,
+    /// `None` if the input doesn't have an answer for this part.
+    pub value: Option<String>,
+    /// Time spent solving this part, without parsing.
+    pub duration: Duration,
+    pub warnings: Vec<String>
+}
+
+/// Time spent in each phase of a single run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timing {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration
+}
+
+/// Object safe counterpart of [`Solution`], so days can be dispatched at runtime.
+pub trait Solver {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    fn solve(&self, input: &str, parts: Parts) -> Result<Vec<Answer>, ParseError>;
+    /// Runs both parts once, discarding the answers.
+    fn time(&self, input: &str) -> Result<Timing, ParseError>;
+}
+
+struct SolutionSolver<S>(PhantomData<S>);
+
+impl<S: Solution> Solver for SolutionSolver<S> {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn solve(&self, input: &str, parts: Parts) -> Result<Vec<Answer>, ParseError> {
+        warnings::take();
+        let start = Instant::now();
+        let input = S::parse(input)?;
+        log::debug!("Day {} parsed in {:?}", S::DAY, start.elapsed());
+        let mut answers = Vec::<Answer>::new();
+
+        if parts.contains(Part::One) {
+            let start = Instant::now();
+            let value = S::part1(&input).map(|answer| answer.to_string());
+            log::debug!("Day {} part 1 solved in {:?}", S::DAY, start.elapsed());
+            answers.push(Answer { part: Part::One, value, duration: start.elapsed(), warnings: warnings::take() });
+        }
+
+        if parts.contains(Part::Two) {
+            let start = Instant::now();
+            let value = S::part2(&input).map(|answer| answer.to_string());
+            log::debug!("Day {} part 2 solved in {:?}", S::DAY, start.elapsed());
+            answers.push(Answer { part: Part::Two, value, duration: start.elapsed(), warnings: warnings::take() });
+        }
+
+        Ok(answers)
+    }
+
+    fn time(&self, input: &str) -> Result<Timing, ParseError> {
+        let start = Instant::now();
+        let input = S::parse(input)?;
+        let parse = start.elapsed();
+
+        let start = Instant::now();
+        std::hint::black_box(S::part1(&input));
+        let part1 = start.elapsed();
+
+        let start = Instant::now();
+        std::hint::black_box(S::part2(&input));
+        let part2 = start.elapsed();
+
+        Ok(Timing { parse, part1, part2 })
+    }
+}
+
+pub fn solver<S: Solution + 'static>() -> Box<dyn Solver> {
+    Box::new(SolutionSolver::<S>(PhantomData))
+}
@@ -0,0 +1,35 @@
+use std::cell::RefCell;
+
+thread_local! {
+    static WARNINGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records a warning about suspicious input or an unexpected state while solving, and logs it.
+///
+/// Warnings are collected per thread, so they must be raised on the thread that called the solver,
+/// not from inside a parallel iterator.
+pub fn warn(message: impl Into<String>) {
+    let message = message.into();
+    log::warn!("{message}");
+    WARNINGS.with(|warnings| warnings.borrow_mut().push(message));
+}
+
+/// Returns and clears the warnings raised on this thread.
+pub fn take() -> Vec<String> {
+    WARNINGS.with(|warnings| warnings.take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warn() {
+        assert_eq!(take(), Vec::<String>::new());
+
+        warn("first");
+        warn(String::from("second"));
+        assert_eq!(take(), vec![String::from("first"), String::from("second")]);
+        assert_eq!(take(), Vec::<String>::new());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,76 @@
+// Runs every solution against the puzzle examples in `examples/dayN/`.
+//
+// Every `<name>.txt` fixture has a `<name>.expected` file next to it with one `Part N: <answer>`
+// line per checked part, in the same format the runners print answers in.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use adventofcode_2023::cli::{self, EXPECTED_EXTENSION};
+use adventofcode_2023::solution::{Part, Parts};
+
+fn examples_directory() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("examples")
+}
+
+fn expected_answers(expected: &str) -> Vec<(Part, Option<String>)> {
+    expected
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (part, answer) = line
+                .strip_prefix("Part ")
+                .and_then(|line| line.split_once(": "))
+                .unwrap_or_else(|| panic!("Expected answer must be 'Part <part>: <answer>', found \"{line}\"!"));
+            let part = part
+                .parse::<u8>()
+                .ok()
+                .and_then(Part::from_number)
+                .unwrap_or_else(|| panic!("Part must be 1 or 2, found \"{part}\"!"));
+            let answer = match answer.trim() {
+                "no answer" => None,
+                answer => Some(String::from(answer))
+            };
+
+            (part, answer)
+        })
+        .collect::<Vec<(Part, Option<String>)>>()
+}
+
+#[test]
+fn test_expected_answers() {
+    assert_eq!(expected_answers("Part 1: 142\n\nPart 2: no answer\n"), vec![
+        (Part::One, Some(String::from("142"))),
+        (Part::Two, None)
+    ]);
+}
+
+#[test]
+fn test_examples() {
+    let mut failures = Vec::<String>::new();
+
+    for solver in adventofcode_2023::solvers() {
+        let fixtures = cli::day_inputs(&examples_directory(), solver.day())
+            .unwrap_or_else(|error| panic!("Day {} needs example fixtures! {error}", solver.day()));
+
+        for fixture in fixtures {
+            let expected_path = fixture.with_extension(EXPECTED_EXTENSION);
+            let expected = fs::read_to_string(&expected_path)
+                .unwrap_or_else(|error| panic!("Can't read {}: {error}", expected_path.display()));
+            let answers = cli::solve(solver.as_ref(), &fixture, Parts::Both)
+                .unwrap_or_else(|error| panic!("{error}"));
+
+            for (part, expected) in expected_answers(&expected) {
+                let actual = answers
+                    .iter()
+                    .find(|answer| answer.part == part)
+                    .and_then(|answer| answer.value.clone());
+                if actual != expected {
+                    failures.push(format!("{} part {}: expected {expected:?}, got {actual:?}",
+                                          fixture.display(), part.number()));
+                }
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "Examples failed:\n{}", failures.join("\n"));
+}
\ No newline at end of file